@@ -1,28 +1,75 @@
+use porter_animation::AnimationFileType;
 use porter_app::{
-    AssetPreview, AssetStatus, Color, Controller, SearchAsset, SearchTerm, Settings,
-    palette::ASSET_TYPE_MODEL,
+    AssetIssue, AssetPreview, AssetStatus, Color, ColumnStatus, Controller, SearchAsset,
+    SearchTerm, Settings, Sort,
+    palette::{ASSET_TYPE_ANIMATION, ASSET_TYPE_MATERIAL, ASSET_TYPE_MODEL},
 };
-use porter_cast::{CastFile, CastId};
+use porter_model::ModelFileType;
 use porter_threads::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
-use std::fs::File;
-use std::io::{Cursor, Read};
-use std::path::PathBuf;
-use std::sync::{Arc, RwLock};
+use std::cmp;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock, mpsc};
 use walkdir::WalkDir;
 
-use crate::cast_model;
+use crate::cast_scan::{self, AssetCounts};
+use crate::file_watcher::{FileChanges, FileWatcher};
+use crate::texture_export::{self, image_file_extension};
+use crate::texture_resolver::TextureResolver;
+use crate::{cast_animation, cast_model, cast_validate, cast_write};
 
-#[derive(Debug)]
+/// The kind of cast data an asset holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AssetType {
+    Model,
+    Animation,
+    Material,
+}
+
+#[derive(Debug, Clone)]
 pub struct Asset {
     pub name: String,
     pub file_name: PathBuf,
-    //pub cast: cast_model::CastNode,
-    pub status: AssetStatus,
+    pub asset_type: AssetType,
+    /// Index of the root node in the cast file
+    pub root_index: usize,
+    /// Index of the model or animation node among its root's children of the same type
+    pub node_index: usize,
+    /// Index of the material among its model's materials, zero for other assets
+    pub material_index: usize,
+    /// Counts gathered while scanning, only the ones matching the asset type are set
+    pub counts: AssetCounts,
+    /// Fields of the metadata node in the asset's root, by display name
+    pub metadata: Vec<(String, String)>,
+    /// Size of the cast file in bytes
+    pub file_size: u64,
+    /// Shared with copies of the asset, so exports can update it without holding the loaded assets
+    pub status: Arc<AssetStatus>,
 }
 
 impl Asset {
     pub fn search(&self) -> SearchAsset {
-        SearchAsset::new(self.name().to_string())
+        let search = SearchAsset::new(self.name().to_string())
+            .bone_count(self.counts.bones)
+            .mesh_count(self.counts.meshes)
+            .frame_count(self.counts.frames)
+            .frame_rate(self.counts.framerate.round() as usize);
+
+        self.metadata.iter().fold(search, |search, (name, value)| {
+            search.tag(&tag_key(name), value)
+        })
+    }
+
+    /// Identifies the asset across rescans of its file: the file, and where in the file the asset is
+    fn key(&self) -> AssetKey {
+        (
+            self.file_name.clone(),
+            self.asset_type,
+            self.root_index,
+            self.node_index,
+            self.material_index,
+        )
     }
 
     /// Returns the name of the asset
@@ -35,27 +82,695 @@ impl Asset {
         &self.status
     }
 
+    /// Returns a short summary of the asset's contents and file size
     fn info(&self) -> String {
-        "N/A".to_string()
+        let counts = &self.counts;
+
+        let mut info = match self.asset_type {
+            AssetType::Model => vec![
+                plural(counts.meshes, "mesh", "meshes"),
+                plural(counts.bones, "bone", "bones"),
+                format!("{} verts", abbreviate_count(counts.vertices)),
+                format!("{} tris", abbreviate_count(counts.faces)),
+                plural(counts.materials, "material", "materials"),
+            ],
+            AssetType::Animation => vec![plural(counts.curves, "curve", "curves")],
+            AssetType::Material => vec![plural(counts.textures, "texture", "textures")],
+        };
+
+        info.push(format_file_size(self.file_size));
+
+        info.join(", ")
+    }
+
+    /// Returns the position of the asset's status in status order
+    fn status_order(&self) -> usize {
+        [
+            AssetStatus::LOADED,
+            AssetStatus::EXPORTED,
+            AssetStatus::ERROR,
+            AssetStatus::PLACEHOLDER,
+            AssetStatus::EXPORTING,
+            AssetStatus::NOT_SUPPORTED,
+            AssetStatus::CHANGED,
+        ]
+        .into_iter()
+        .position(|status| self.status.is(status))
+        .unwrap_or(usize::MAX)
+    }
+
+    /// Compares two assets by the given column, in ascending order
+    fn compare(&self, other: &Self, column: usize) -> cmp::Ordering {
+        match column {
+            COLUMN_NAME => natural_cmp(&self.name, &other.name),
+            COLUMN_TYPE => self.type_name().cmp(&other.type_name()),
+            COLUMN_STATUS => self.status_order().cmp(&other.status_order()),
+            COLUMN_INFO => {
+                let stats = |asset: &Self| {
+                    let counts = &asset.counts;
+
+                    (
+                        counts.meshes,
+                        counts.bones,
+                        counts.vertices,
+                        counts.faces,
+                        counts.materials,
+                        counts.curves,
+                        counts.textures,
+                        asset.file_size,
+                    )
+                };
+
+                stats(self).cmp(&stats(other))
+            }
+            _ => cmp::Ordering::Equal,
+        }
     }
 
     /// Returns the color of the asset type
     fn color(&self) -> Color {
-        ASSET_TYPE_MODEL
+        match self.asset_type {
+            AssetType::Model => ASSET_TYPE_MODEL,
+            AssetType::Animation => ASSET_TYPE_ANIMATION,
+            AssetType::Material => ASSET_TYPE_MATERIAL,
+        }
     }
 
     /// Returns the type name kf the asset
     fn type_name(&self) -> String {
-        "Model".to_string()
+        match self.asset_type {
+            AssetType::Model => "Model".to_string(),
+            AssetType::Animation => "Animation".to_string(),
+            AssetType::Material => "Material".to_string(),
+        }
+    }
+
+    /// Re-parses the asset for preview, along with any issues found in its node and the structure of its file
+    fn preview(&self, settings: &Settings) -> AssetPreview {
+        let mut preview = self.preview_contents(settings);
+
+        if !self.metadata.is_empty() {
+            preview = AssetPreview::Metadata(self.metadata.clone(), Box::new(preview));
+        }
+
+        match cast_scan::read_cast_structure(&self.file_name) {
+            Ok(structure) => AssetPreview::Structure(structure, Box::new(preview)),
+            Err(e) => {
+                eprintln!("Failed to read structure of {}: {e}", self.name);
+                preview
+            }
+        }
+    }
+
+    fn preview_contents(&self, settings: &Settings) -> AssetPreview {
+        let file = match cast_model::read_cast_file(&self.file_name) {
+            Ok(file) => file,
+            Err(e) => {
+                eprintln!("Failed to preview {}: {e}", self.name);
+                return AssetPreview::PreviewError;
+            }
+        };
+
+        let (preview, issues) = match self.asset_type {
+            AssetType::Model => {
+                let Ok(model_node) =
+                    cast_model::find_model_node(&file, self.root_index, self.node_index)
+                else {
+                    return AssetPreview::PreviewError;
+                };
+
+                let path = format!("root[{}]/model[{}]", self.root_index, self.node_index);
+
+                let mut issues = cast_validate::validate_model_node(model_node, &path);
+
+                let resolver =
+                    TextureResolver::new(&self.file_name, settings.texture_directories());
+
+                let preview = cast_model::process_model_node(model_node)
+                    .map(|mut model| {
+                        let images = cast_model::load_model_images(&model, &resolver);
+                        issues.extend(cast_validate::validate_textures(&model, &resolver, &path));
+                        cast_model::convert_hair_to_meshes(&mut model);
+                        AssetPreview::Model(self.name(), model, images)
+                    })
+                    .unwrap_or(AssetPreview::PreviewError);
+
+                (preview, issues)
+            }
+            AssetType::Animation => {
+                let path = format!("root[{}]/animation[{}]", self.root_index, self.node_index);
+
+                let issues =
+                    cast_animation::find_animation_node(&file, self.root_index, self.node_index)
+                        .map(|node| cast_validate::validate_animation_node(node, &path))
+                        .unwrap_or_default();
+
+                (AssetPreview::NotSupported, issues)
+            }
+            AssetType::Material => {
+                let Ok(material_node) =
+                    cast_model::find_model_node(&file, self.root_index, self.node_index).and_then(
+                        |model_node| {
+                            cast_model::find_material_node(model_node, self.material_index)
+                        },
+                    )
+                else {
+                    return AssetPreview::PreviewError;
+                };
+
+                let path = format!(
+                    "root[{}]/model[{}]/material[{}]",
+                    self.root_index, self.node_index, self.material_index
+                );
+
+                let resolver =
+                    TextureResolver::new(&self.file_name, settings.texture_directories());
+
+                let material = cast_model::process_material_node(material_node);
+
+                let (images, unresolved) = cast_model::load_material_images(&material, &resolver);
+
+                let issues = unresolved
+                    .into_iter()
+                    .map(|(slot, e)| AssetIssue::warning(&path, Some(slot.as_str()), e))
+                    .collect();
+
+                (AssetPreview::Material(self.name(), images), issues)
+            }
+        };
+
+        if issues.is_empty() {
+            preview
+        } else {
+            AssetPreview::Issues(issues, Box::new(preview))
+        }
+    }
+
+    /// Re-parses the asset and writes it to every enabled file type for its asset type,
+    /// into the given directory of the output directory
+    pub fn export(
+        &self,
+        settings: &Settings,
+        directory_name: &str,
+        cancel: &AtomicBool,
+    ) -> Result<ExportOutcome, String> {
+        let output_directory = settings.output_directory().join(directory_name);
+
+        match self.asset_type {
+            AssetType::Model => {
+                let file = cast_model::read_cast_file(&self.file_name)?;
+                let model_node =
+                    cast_model::find_model_node(&file, self.root_index, self.node_index)?;
+
+                let mut model = cast_model::process_model_node(model_node)
+                    .ok_or_else(|| "Failed to process model".to_string())?;
+
+                // Converted before scaling, so the tubes are scaled with the strands.
+                if settings.export_hair_as_meshes() {
+                    cast_model::convert_hair_to_meshes(&mut model);
+                }
+
+                let resolver =
+                    TextureResolver::new(&self.file_name, settings.texture_directories());
+
+                // Images are written first, so the materials of the exported model point at them.
+                let images = texture_export::export_model_images(
+                    &mut model,
+                    &resolver,
+                    &output_directory,
+                    settings,
+                    cancel,
+                )
+                .inspect_err(|_| remove_partial_output(&output_directory, &[]))?;
+
+                let Some(images) = images else {
+                    remove_partial_output(&output_directory, &[]);
+                    return Ok(ExportOutcome::Canceled);
+                };
+
+                if let Some(scale) = settings.auto_scale_factor(1.0) {
+                    model.scale(scale);
+                }
+
+                let file_types: Vec<_> = settings
+                    .model_file_types()
+                    .into_iter()
+                    .map(|file_type| (file_type, model_file_extension(file_type)))
+                    .collect();
+
+                let outcome = write_outputs(
+                    &output_directory,
+                    &self.name,
+                    &file_types,
+                    cancel,
+                    |file_name, file_type| match file_type {
+                        // Written from the node it was read from, so hashes, metadata and anything else
+                        // the model doesn't hold are kept.
+                        ModelFileType::Cast => cast_write::write_cast_file(
+                            &cast_write::model_file(&model, Some(model_node)),
+                            file_name,
+                        )
+                        .map_err(|e| format!("Failed to export {file_type:?}: {e}")),
+                        _ => model
+                            .save(file_name, file_type)
+                            .map_err(|e| format!("Failed to export {file_type:?}: {e:?}")),
+                    },
+                );
+
+                // Images without the model files that use them are removed along with the output directory.
+                if !matches!(outcome, Ok(ExportOutcome::Exported)) {
+                    texture_export::remove_images(&output_directory, &images);
+                    remove_partial_output(&output_directory, &[]);
+                }
+
+                outcome
+            }
+            AssetType::Animation => {
+                let mut animation = cast_animation::load_animation_file(
+                    &self.file_name,
+                    self.root_index,
+                    self.node_index,
+                )?;
+
+                if let Some(scale) = settings.auto_scale_factor(1.0) {
+                    animation.scale(scale);
+                }
+
+                let file_types: Vec<_> = settings
+                    .anim_file_types()
+                    .into_iter()
+                    .map(|file_type| (file_type, anim_file_extension(file_type)))
+                    .collect();
+
+                write_outputs(
+                    &output_directory,
+                    &self.name,
+                    &file_types,
+                    cancel,
+                    |file_name, file_type| {
+                        animation
+                            .save(file_name, file_type)
+                            .map_err(|e| format!("Failed to export {file_type:?}: {e:?}"))
+                    },
+                )
+            }
+            AssetType::Material => {
+                let material = cast_model::read_cast_file(&self.file_name).and_then(|file| {
+                    cast_model::find_model_node(&file, self.root_index, self.node_index)
+                        .and_then(|model_node| {
+                            cast_model::find_material_node(model_node, self.material_index)
+                        })
+                        .map(cast_model::process_material_node)
+                })?;
+
+                // Nothing to write, so no empty directory is left behind either.
+                if material.textures.is_empty() {
+                    return Ok(ExportOutcome::Exported);
+                }
+
+                let resolver =
+                    TextureResolver::new(&self.file_name, settings.texture_directories());
+
+                std::fs::create_dir_all(&output_directory)
+                    .map_err(|e| format!("Could not create output directory: {e}"))?;
+
+                let extension = image_file_extension(settings.image_file_type());
+
+                let mut written = Vec::new();
+
+                // One image per slot, named after the material and the slot.
+                for texture in &material.textures {
+                    if cancel.load(Ordering::Relaxed) {
+                        remove_partial_output(&output_directory, &written);
+                        return Ok(ExportOutcome::Canceled);
+                    }
+
+                    let output = output_directory.join(format!(
+                        "{}_{}.{extension}",
+                        self.name, texture.texture_alias
+                    ));
+
+                    let saved = resolver
+                        .resolve(&texture.file_name)
+                        .map_err(|e| {
+                            format!("Failed to find texture {}: {e}", texture.texture_alias)
+                        })
+                        .and_then(|(path, image_file_type)| {
+                            texture_export::save_texture(
+                                &path,
+                                image_file_type,
+                                texture.texture_usage,
+                                &output,
+                                settings,
+                            )
+                        });
+
+                    // The failing image may have been written partially.
+                    if let Err(e) = saved {
+                        written.push(output);
+                        remove_partial_output(&output_directory, &written);
+                        return Err(e);
+                    }
+
+                    written.push(output);
+                }
+
+                Ok(ExportOutcome::Exported)
+            }
+        }
     }
 }
 
-pub type LoadedAssets = Arc<RwLock<Vec<Asset>>>;
+/// Maximum number of files scanned before the found assets are published during a directory load
+const LOAD_BATCH_SIZE: usize = 256;
+
+/// Whether or not the path has a cast file extension
+pub fn is_cast_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("cast"))
+}
+
+/// Columns shown for each asset, in the order they are registered in main
+const COLUMN_NAME: usize = 0;
+const COLUMN_TYPE: usize = 1;
+const COLUMN_STATUS: usize = 2;
+const COLUMN_INFO: usize = 3;
+
+/// Compares two names so that embedded numbers are ordered by value, `lod2` comes before `lod10`
+fn natural_cmp(a: &str, b: &str) -> cmp::Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
 
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return cmp::Ordering::Equal,
+            (None, Some(_)) => return cmp::Ordering::Less,
+            (Some(_), None) => return cmp::Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let take_number = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut digits = String::new();
+
+                    while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+                        digits.push(c);
+                    }
+
+                    digits
+                };
+
+                let x = take_number(&mut a);
+                let y = take_number(&mut b);
+
+                let x_trimmed = x.trim_start_matches('0');
+                let y_trimmed = y.trim_start_matches('0');
+
+                // Compare by length first so numbers of any size are ordered by value
+                let ordering = x_trimmed
+                    .len()
+                    .cmp(&y_trimmed.len())
+                    .then_with(|| x_trimmed.cmp(y_trimmed))
+                    .then_with(|| x.len().cmp(&y.len()));
+
+                if ordering != cmp::Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x
+                    .to_lowercase()
+                    .cmp(y.to_lowercase())
+                    .then_with(|| x.cmp(&y));
+
+                if ordering != cmp::Ordering::Equal {
+                    return ordering;
+                }
+
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
+
+/// Formats a count with its singular or plural noun
+fn plural(count: usize, singular: &str, plural: &str) -> String {
+    if count == 1 {
+        format!("{count} {singular}")
+    } else {
+        format!("{count} {plural}")
+    }
+}
+
+/// Shortens large counts, 53210 becomes 53k
+fn abbreviate_count(count: usize) -> String {
+    match count {
+        0..1_000 => count.to_string(),
+        1_000..10_000 => format!("{:.1}k", count as f64 / 1_000.0),
+        10_000..1_000_000 => format!("{}k", (count + 500) / 1_000),
+        _ => format!("{:.1}M", count as f64 / 1_000_000.0),
+    }
+}
+
+/// Formats a file size in bytes with the largest fitting unit
+fn format_file_size(size: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if size < 1024 {
+        return format!("{size} B");
+    }
+
+    let mut size = size as f64 / 1024.0;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// An asset that failed to export
+#[derive(Debug)]
+pub struct ExportFailure {
+    pub file_name: PathBuf,
+    pub name: String,
+    pub error: String,
+}
+
+/// What exporting a set of assets did
 #[derive(Debug)]
+pub struct ExportSummary {
+    pub exported: usize,
+    /// Assets left out because no file type is enabled for their asset type
+    pub skipped: usize,
+    pub failures: Vec<ExportFailure>,
+}
+
+/// The outcome of an asset export that didn't fail
+pub enum ExportOutcome {
+    Exported,
+    Canceled,
+}
+
+/// Whether any file type is enabled to export the given asset type to.
+fn has_export_file_types(asset_type: AssetType, settings: &Settings) -> bool {
+    match asset_type {
+        AssetType::Model => !settings.model_file_types().is_empty(),
+        AssetType::Animation => !settings.anim_file_types().is_empty(),
+        // Materials export their textures in the image file type, which is always set
+        AssetType::Material => true,
+    }
+}
+
+/// Returns the file extension written for the given model file type
+fn model_file_extension(file_type: ModelFileType) -> &'static str {
+    match file_type {
+        ModelFileType::Obj => "obj",
+        ModelFileType::Smd => "smd",
+        ModelFileType::XnaLara => "mesh.ascii",
+        ModelFileType::XModelExport => "xmodel_export",
+        ModelFileType::Cast => "cast",
+        ModelFileType::Maya => "ma",
+        ModelFileType::Fbx => "fbx",
+    }
+}
+
+/// Returns the file extension written for the given animation file type
+fn anim_file_extension(file_type: AnimationFileType) -> &'static str {
+    match file_type {
+        AnimationFileType::Cast => "cast",
+    }
+}
+
+/// Writes one output file per file type, checking for cancellation between each one
+fn write_outputs<T: Copy>(
+    output_directory: &Path,
+    name: &str,
+    file_types: &[(T, &'static str)],
+    cancel: &AtomicBool,
+    save: impl Fn(&Path, T) -> Result<(), String>,
+) -> Result<ExportOutcome, String> {
+    std::fs::create_dir_all(output_directory)
+        .map_err(|e| format!("Could not create output directory: {e}"))?;
+
+    let mut written = Vec::new();
+
+    for &(file_type, extension) in file_types {
+        if cancel.load(Ordering::Relaxed) {
+            remove_partial_output(output_directory, &written);
+            return Ok(ExportOutcome::Canceled);
+        }
+
+        let file_name = output_directory.join(format!("{name}.{extension}"));
+
+        // The formats written before the failing one are removed too, so no half export is left behind.
+        if let Err(e) = save(&file_name, file_type) {
+            written.push(file_name);
+            remove_partial_output(output_directory, &written);
+            return Err(e);
+        }
+
+        written.push(file_name);
+    }
+
+    Ok(ExportOutcome::Exported)
+}
+
+/// The directory each asset is exported to, named after the asset.
+///
+/// Assets sharing a name would write over each other's files, so they're also named after their file
+/// and where they are in it, and numbered if that's still not enough.
+fn export_directory_names(assets: &[Asset]) -> Vec<String> {
+    // File systems may ignore case, so names are compared without it.
+    let mut name_counts: HashMap<String, usize> = HashMap::new();
+
+    for asset in assets {
+        *name_counts.entry(asset.name.to_lowercase()).or_default() += 1;
+    }
+
+    let mut used_names = HashSet::new();
+
+    assets
+        .iter()
+        .map(|asset| {
+            let base = if name_counts[&asset.name.to_lowercase()] > 1 {
+                let stem = asset
+                    .file_name
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .unwrap_or_default();
+
+                format!(
+                    "{}_{stem}_{}_{}",
+                    asset.name, asset.root_index, asset.node_index
+                )
+            } else {
+                asset.name.clone()
+            };
+
+            let mut name = base.clone();
+            let mut index = 1;
+
+            while !used_names.insert(name.to_lowercase()) {
+                name = format!("{base}_{index}");
+                index += 1;
+            }
+
+            name
+        })
+        .collect()
+}
+
+/// Deletes output files that were written before an export was stopped,
+/// and the output directory itself if nothing else is left in it
+fn remove_partial_output(output_directory: &Path, files: &[PathBuf]) {
+    for file in files {
+        let _ = std::fs::remove_file(file);
+    }
+
+    // Only succeeds when the directory is empty
+    let _ = std::fs::remove_dir(output_directory);
+}
+
+/// The key a metadata field is searched by, its display name without spaces, so `Up Axis` is `upaxis:`
+fn tag_key(name: &str) -> String {
+    name.replace(' ', "").to_lowercase()
+}
+
+/// Orders the assets by the given column, returning where each asset moved to.
+fn sort_assets(assets: &mut Vec<Asset>, active: ColumnStatus) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..assets.len()).collect();
+
+    // Stable, so assets that compare equal keep their relative order
+    order.sort_by(|a, b| {
+        let ordering = assets[*a].compare(&assets[*b], active.index);
+
+        match active.sort {
+            Sort::Descending => ordering.reverse(),
+            _ => ordering,
+        }
+    });
+
+    let mut new_index = vec![0; order.len()];
+
+    for (new, old) in order.iter().enumerate() {
+        new_index[*old] = new;
+    }
+
+    let mut sorted: Vec<Option<Asset>> = assets.drain(..).map(Some).collect();
+
+    *assets = order
+        .into_iter()
+        .filter_map(|index| sorted[index].take())
+        .collect();
+
+    new_index
+}
+
+/// Indices of the loaded assets matching the search term
+fn search_results(assets: &[Asset], search: &SearchTerm) -> Vec<usize> {
+    // Terms that look like tags are only tags when some asset has the key, otherwise they're part of a name.
+    let tag_keys: HashSet<String> = assets
+        .iter()
+        .flat_map(|asset| asset.metadata.iter().map(|(name, _)| tag_key(name)))
+        .collect();
+
+    let search = search.with_known_tags(|key| tag_keys.contains(key));
+
+    assets
+        .into_par_iter()
+        .enumerate()
+        .filter_map(|(index, asset)| {
+            if search.matches(asset.search()) {
+                Some(index)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+pub type LoadedAssets = Arc<RwLock<Vec<Asset>>>;
+pub type SearchAssets = Arc<RwLock<Option<Vec<usize>>>>;
+pub type LastSearch = Arc<RwLock<Option<SearchTerm>>>;
+pub type ActiveSort = Arc<RwLock<Option<ColumnStatus>>>;
+type AssetKey = (PathBuf, AssetType, usize, usize, usize);
+
+/// Whenever both are held, `loaded_assets` is locked before `search_assets`.
 pub struct AssetManager {
-    search_assets: Arc<RwLock<Option<Vec<usize>>>>,
+    search_assets: SearchAssets,
     loaded_assets: LoadedAssets,
+    export_cancel: Arc<AtomicBool>,
+    /// The search term the search results were built from, re-applied when watched files change
+    last_search: LastSearch,
+    /// The column the assets were last sorted by, re-applied when watched files change
+    active_sort: ActiveSort,
+    /// The files or directory given to the last load
+    watch_roots: RwLock<Vec<PathBuf>>,
+    watcher: Mutex<Option<FileWatcher>>,
+    /// The file of the last previewed asset
+    previewed_file: Arc<RwLock<Option<PathBuf>>>,
 }
 
 impl AssetManager {
@@ -64,24 +779,333 @@ impl AssetManager {
         AssetManager {
             search_assets: Arc::new(RwLock::new(None)),
             loaded_assets: Arc::new(RwLock::new(Vec::new())),
+            export_cancel: Arc::new(AtomicBool::new(false)),
+            last_search: Arc::new(RwLock::new(None)),
+            active_sort: Arc::new(RwLock::new(None)),
+            watch_roots: RwLock::new(Vec::new()),
+            watcher: Mutex::new(None),
+            previewed_file: Arc::new(RwLock::new(None)),
         }
     }
 
-    // TODO: Think of a better way of doing this, currently reads the entire file when initially loading
-    //       which is not ideal for large files.
-    pub fn ensure_has_model<R: Read>(mut reader: R) -> Result<(), String> {
-        let cast = CastFile::read(&mut reader).map_err(|e| format!("Error loading file: {e}"))?;
+    /// Builds an asset for every model and animation in the given cast file, whose type is enabled in the settings.
+    fn load_assets(settings: &Settings, file_name: &Path) -> Vec<Asset> {
+        let detected = match cast_scan::scan_cast_file(file_name) {
+            Ok(detected) if !detected.is_empty() => detected,
+            Ok(_) => {
+                eprintln!(
+                    "Skipping {}: No model or animation found",
+                    file_name.display()
+                );
+                return Vec::new();
+            }
+            Err(e) => {
+                eprintln!("Skipping {}: {e}", file_name.display());
+                return Vec::new();
+            }
+        };
 
-        let found = cast
-            .roots()
+        let file_size = std::fs::metadata(file_name)
+            .map(|metadata| metadata.len())
+            .unwrap_or_default();
+
+        let stem = file_name
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+
+        // Unnamed nodes fall back to the file stem, numbered when the file holds more than one model or animation.
+        let numbered = detected
             .iter()
-            .any(|root| root.children_of_type(CastId::Model).next().is_some());
+            .filter(|asset| asset.asset_type != AssetType::Material)
+            .count()
+            > 1;
 
-        if found {
-            Ok(())
-        } else {
-            Err("No model found".to_string())
+        let mut index = 0;
+
+        detected
+            .into_iter()
+            .map(|asset| {
+                let fallback = match asset.asset_type {
+                    // Materials are named after the model they belong to.
+                    AssetType::Material => {
+                        let model_index = index.saturating_sub(1);
+
+                        if numbered {
+                            format!("{stem}_{model_index}_material{}", asset.material_index)
+                        } else {
+                            format!("{stem}_material{}", asset.material_index)
+                        }
+                    }
+                    _ => {
+                        index += 1;
+
+                        if numbered {
+                            format!("{stem}_{}", index - 1)
+                        } else {
+                            stem.to_string()
+                        }
+                    }
+                };
+
+                (fallback, asset)
+            })
+            .filter(|(_, asset)| match asset.asset_type {
+                AssetType::Model => settings.load_models(),
+                AssetType::Animation => settings.load_animations(),
+                AssetType::Material => cfg!(feature = "materials") && settings.load_materials(),
+            })
+            .map(|(fallback, asset)| Asset {
+                name: asset
+                    .name
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or(fallback),
+                file_name: file_name.to_path_buf(),
+                asset_type: asset.asset_type,
+                root_index: asset.root_index,
+                node_index: asset.node_index,
+                material_index: asset.material_index,
+                counts: asset.counts,
+                metadata: asset.metadata,
+                file_size,
+                status: Arc::new(AssetStatus::LOADED),
+            })
+            .collect()
+    }
+
+    /// Scans the given cast files in parallel, keeping the order they were given in.
+    fn scan_files(settings: &Settings, files: Vec<PathBuf>) -> Vec<Asset> {
+        // Only files with a model or animation node produce assets
+        let assets: Vec<Vec<Asset>> = files
+            .into_par_iter()
+            .map(|file_name| Self::load_assets(settings, &file_name))
+            .collect();
+
+        assets.into_iter().flatten().collect()
+    }
+
+    /// Replaces the loaded assets with a freshly loaded set.
+    fn replace_assets(&self, assets: Vec<Asset>) -> Result<(), String> {
+        *self.search_assets.write().unwrap() = None;
+        *self.last_search.write().unwrap() = None;
+
+        let mut loaded = self.loaded_assets.write();
+
+        match loaded.as_mut() {
+            Ok(loaded) => {
+                **loaded = assets;
+                Ok(())
+            }
+            Err(_) => Err("Failed to acquire write lock on loaded assets".to_string()),
+        }
+    }
+
+    /// The files that produced at least one loaded asset.
+    pub fn loaded_files(&self) -> HashSet<PathBuf> {
+        self.loaded_assets
+            .read()
+            .unwrap()
+            .iter()
+            .map(|asset| asset.file_name.clone())
+            .collect()
+    }
+
+    /// Exports the given loaded assets in parallel, reporting progress and returning what was exported and what failed.
+    pub fn export_assets(
+        &self,
+        settings: &Settings,
+        assets: Vec<usize>,
+        progress: impl Fn(u32) + Sync,
+    ) -> ExportSummary {
+        // Copied out, so file changes and sorting aren't held up for the whole export
+        let assets: Vec<Asset> = {
+            let loaded_assets = self.loaded_assets.read().unwrap();
+
+            assets
+                .iter()
+                .filter_map(|index| loaded_assets.get(*index).cloned())
+                .collect()
+        };
+
+        // Exporting a model already writes the textures of its materials, so they aren't exported again.
+        let models: HashSet<(&Path, usize, usize)> = assets
+            .iter()
+            .filter(|asset| asset.asset_type == AssetType::Model)
+            .map(|asset| {
+                (
+                    asset.file_name.as_path(),
+                    asset.root_index,
+                    asset.node_index,
+                )
+            })
+            .collect();
+
+        let assets: Vec<Asset> = assets
+            .iter()
+            .filter(|asset| {
+                asset.asset_type != AssetType::Material
+                    || !models.contains(&(
+                        asset.file_name.as_path(),
+                        asset.root_index,
+                        asset.node_index,
+                    ))
+            })
+            .cloned()
+            .collect();
+
+        // Without a file type to write, an export would only leave an empty directory behind.
+        let (assets, skipped): (Vec<Asset>, Vec<Asset>) = assets
+            .into_iter()
+            .partition(|asset| has_export_file_types(asset.asset_type, settings));
+
+        for asset in &assets {
+            asset.status.set(AssetStatus::EXPORTING);
+        }
+
+        let directory_names = export_directory_names(&assets);
+
+        let total = assets.len().max(1);
+        let exported = AtomicUsize::new(0);
+        let finished = AtomicUsize::new(0);
+        let failures = Mutex::new(Vec::new());
+
+        self.export_cancel.store(false, Ordering::Relaxed);
+
+        assets
+            .into_par_iter()
+            .zip(directory_names)
+            .for_each(|(asset, directory_name)| {
+                if self.export_cancel.load(Ordering::Relaxed) {
+                    asset.status.set(AssetStatus::LOADED);
+                    return;
+                }
+
+                match asset.export(settings, &directory_name, &self.export_cancel) {
+                    Ok(ExportOutcome::Exported) => {
+                        asset.status.set(AssetStatus::EXPORTED);
+                        exported.fetch_add(1, Ordering::Relaxed);
+                    }
+                    Ok(ExportOutcome::Canceled) => {
+                        asset.status.set(AssetStatus::LOADED);
+                        return;
+                    }
+                    Err(e) => {
+                        asset.status.set(AssetStatus::ERROR);
+
+                        failures.lock().unwrap().push(ExportFailure {
+                            file_name: asset.file_name.clone(),
+                            name: asset.name(),
+                            error: e,
+                        });
+                    }
+                }
+
+                let finished = finished.fetch_add(1, Ordering::Relaxed) + 1;
+
+                progress((finished * 100 / total) as u32);
+            });
+
+        ExportSummary {
+            exported: exported.into_inner(),
+            skipped: skipped.len(),
+            failures: failures.into_inner().unwrap(),
+        }
+    }
+
+    /// Stops watching the previous load, and remembers what to watch for the next one.
+    fn reset_watch(&self, roots: Vec<PathBuf>) {
+        *self.watcher.lock().unwrap() = None;
+        *self.watch_roots.write().unwrap() = roots;
+    }
+
+    /// Updates the loaded assets and search results to match the files that changed on disk.
+    ///
+    /// Assets of modified files are replaced in place and marked as changed, so the list doesn't jump around,
+    /// and the active sort is re-applied so added assets are listed in order.
+    /// Returns where each previously visible asset is visible now, if it still is.
+    fn apply_file_changes(
+        settings: &Settings,
+        loaded_assets: &LoadedAssets,
+        search_assets: &SearchAssets,
+        last_search: &LastSearch,
+        active_sort: &ActiveSort,
+        changes: &FileChanges,
+    ) -> Vec<Option<usize>> {
+        // Scan before taking the locks, so the list stays responsive
+        let modified: Vec<(&PathBuf, Vec<Asset>)> = changes
+            .modified
+            .iter()
+            .map(|file_name| (file_name, Self::load_assets(settings, file_name)))
+            .collect();
+        let added = Self::scan_files(settings, changes.added.clone());
+
+        let mut loaded = loaded_assets.write().unwrap();
+        let mut search_assets = search_assets.write().unwrap();
+
+        let visible_keys: Vec<AssetKey> = visible_assets(&loaded, search_assets.as_deref())
+            .map(Asset::key)
+            .collect();
+
+        loaded.retain(|asset| !changes.removed.contains(&asset.file_name));
+
+        for (file_name, assets) in modified {
+            // The file is likely still being written, keep the old assets until it scans again
+            if assets.is_empty() {
+                loaded
+                    .iter()
+                    .filter(|asset| &asset.file_name == file_name)
+                    .for_each(|asset| asset.status.set(AssetStatus::CHANGED));
+                continue;
+            }
+
+            for asset in &assets {
+                asset.status.set(AssetStatus::CHANGED);
+            }
+
+            let position = loaded
+                .iter()
+                .position(|asset| &asset.file_name == file_name);
+
+            loaded.retain(|asset| &asset.file_name != file_name);
+
+            let position = position.unwrap_or(loaded.len());
+
+            loaded.splice(position..position, assets);
+        }
+
+        loaded.extend(added);
+
+        // Added assets go where the active sort puts them, rather than at the end
+        if let Some(active) = *active_sort.read().unwrap() {
+            sort_assets(&mut loaded, active);
         }
+
+        *search_assets = last_search
+            .read()
+            .unwrap()
+            .as_ref()
+            .map(|search| search_results(&loaded, search));
+
+        let positions: HashMap<AssetKey, usize> = visible_assets(&loaded, search_assets.as_deref())
+            .enumerate()
+            .map(|(position, asset)| (asset.key(), position))
+            .collect();
+
+        visible_keys
+            .into_iter()
+            .map(|key| positions.get(&key).copied())
+            .collect()
+    }
+}
+
+/// The assets in the order they're listed, either the search results or every loaded asset.
+fn visible_assets<'a>(
+    loaded: &'a [Asset],
+    search: Option<&'a [usize]>,
+) -> Box<dyn Iterator<Item = &'a Asset> + 'a> {
+    match search {
+        Some(search) => Box::new(search.iter().filter_map(|index| loaded.get(*index))),
+        None => Box::new(loaded.iter()),
     }
 }
 
@@ -95,10 +1119,18 @@ impl porter_app::AssetManager for AssetManager {
         true
     }
 
+    fn supports_watching(&self) -> bool {
+        true
+    }
+
+    fn supports_texture_directories(&self) -> bool {
+        true
+    }
+
     /// Gets information about the specific asset, in the form of column data.
     fn assets_info(&self, index: usize) -> Vec<(String, Option<Color>)> {
-        let search_lock = self.search_assets.read().unwrap();
         let loaded_assets_lock = self.loaded_assets.read().unwrap();
+        let search_lock = self.search_assets.read().unwrap();
 
         // Change this when new porter_lib out
         let asset_index = if let Some(search) = search_lock.as_ref() {
@@ -124,12 +1156,13 @@ impl porter_app::AssetManager for AssetManager {
 
     /// The number of visible assets, whether they are search results, or just loaded.
     fn assets_visible(&self) -> usize {
+        let loaded_assets_lock = self.loaded_assets.read().unwrap();
         let search_lock = self.search_assets.read().unwrap();
 
         if let Some(indexes) = search_lock.as_ref() {
             indexes.len()
         } else {
-            self.loaded_assets.read().unwrap().len()
+            loaded_assets_lock.len()
         }
     }
 
@@ -139,122 +1172,187 @@ impl porter_app::AssetManager for AssetManager {
     }
 
     fn search(&self, search: Option<SearchTerm>) {
-        let Some(search) = search else {
-            *self.search_assets.write().unwrap() = None;
-            return;
-        };
+        let results = search
+            .as_ref()
+            .map(|search| search_results(&self.loaded_assets.read().unwrap(), search));
 
-        let loaded_assets = self.loaded_assets.read().unwrap();
+        *self.search_assets.write().unwrap() = results;
+        *self.last_search.write().unwrap() = search;
+    }
 
-        let results = loaded_assets
-            .as_slice()
-            .into_par_iter()
-            .enumerate()
-            .filter_map(|(index, asset)| {
-                if search.matches(asset.search()) {
-                    Some(index)
+    /// Sorts the loaded assets by the given column, or re-applies the active sort when no column is given.
+    fn sort(&self, column: Option<usize>, statuses: Vec<ColumnStatus>) -> Vec<ColumnStatus> {
+        let mut statuses = statuses;
+
+        if let Some(column) = column {
+            for status in &mut statuses {
+                status.sort = if status.index == column {
+                    match status.sort {
+                        Sort::Ascending => Sort::Descending,
+                        Sort::None | Sort::Descending => Sort::Ascending,
+                    }
                 } else {
-                    None
-                }
-            })
-            .collect();
+                    Sort::None
+                };
+            }
+        }
 
-        *self.search_assets.write().unwrap() = Some(results);
-    }
+        let active = statuses
+            .iter()
+            .find(|status| !matches!(status.sort, Sort::None))
+            .copied();
 
-    /// Loads one or more given file in async.
-    fn load_files(&self, _settings: Settings, files: Vec<PathBuf>) -> Result<(), String> {
-        for file_name in &files {
-            if let Some(ext) = file_name.extension().and_then(|ext| ext.to_str()) {
-                if ext == "cast" {
-                    let asset = Asset {
-                        name: file_name
-                            .file_stem()
-                            .and_then(|stem| stem.to_str())
-                            .unwrap_or_default()
-                            .to_string(),
-                        file_name: file_name.to_path_buf(),
-                        status: AssetStatus::LOADED,
-                    };
-
-                    // Assign to shared state
-                    let mut loaded = self.loaded_assets.write();
-                    match loaded.as_mut() {
-                        Ok(loaded) => {
-                            loaded.push(asset);
-                        }
-                        Err(_) => {
-                            return Err("Failed to acquire write lock on loaded assets".to_string());
-                        }
-                    }
-                }
+        *self.active_sort.write().unwrap() = active;
+
+        let Some(active) = active else {
+            return statuses;
+        };
+
+        let mut loaded_assets = self.loaded_assets.write().unwrap();
+        let mut search_assets = self.search_assets.write().unwrap();
+
+        let new_index = sort_assets(&mut loaded_assets, active);
+
+        // Search results point at loaded assets, so they have to follow them to their new place
+        if let Some(search) = search_assets.as_mut() {
+            for index in search.iter_mut() {
+                *index = new_index[*index];
             }
+
+            search.sort_unstable();
         }
-        Ok(())
+
+        statuses
+    }
+
+    /// Loads one or more given file in async.
+    fn load_files(&self, settings: Settings, files: Vec<PathBuf>) -> Result<(), String> {
+        let files: Vec<PathBuf> = files
+            .into_iter()
+            .filter(|file_name| is_cast_file(file_name))
+            .collect();
+
+        self.reset_watch(files.clone());
+        self.replace_assets(Self::scan_files(&settings, files))
     }
 
-    /// Recursively loads all files found under the provided directory.
-    fn load_directory(&self, _settings: Settings, directory: PathBuf) -> Result<(), String> {
+    /// Recursively loads all files found under the provided directory, publishing assets in batches as they are scanned.
+    fn load_directory(
+        &self,
+        settings: Settings,
+        directory: PathBuf,
+        controller: Controller,
+    ) -> Result<(), String> {
         if !directory.is_dir() {
             return Err("Provided path is not a directory".to_string());
         }
 
-        let mut discovered = Vec::new();
+        self.reset_watch(vec![directory.clone()]);
+        self.export_cancel.store(false, Ordering::Relaxed);
+        self.replace_assets(Vec::new())?;
 
-        for entry in WalkDir::new(&directory).into_iter().filter_map(|e| e.ok()) {
-            let path = entry.path();
-            if !path.is_file() {
-                continue;
-            }
+        controller.progress_update(false, 0);
+
+        let cancel = &self.export_cancel;
+        let discovered = AtomicUsize::new(0);
+        let (sender, receiver) = mpsc::channel::<PathBuf>();
 
-            if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
-                if ext.eq_ignore_ascii_case("cast") {
-                    let reader = File::open(path).map_err(|e| format!("Could not open: {e}"))?;
+        std::thread::scope(|scope| {
+            let directory = &directory;
+            let discovered = &discovered;
 
-                    // Ensure the file has a model node
-                    if Self::ensure_has_model(reader).is_err() {
-                        continue;
+            // Walk the tree on its own thread, so scanning can start with the first files found
+            scope.spawn(move || {
+                for entry in WalkDir::new(directory).into_iter().filter_map(|e| e.ok()) {
+                    if cancel.load(Ordering::Relaxed) {
+                        break;
                     }
 
-                    let name = path
-                        .file_stem()
-                        .and_then(|s| s.to_str())
-                        .unwrap_or_default()
-                        .to_string();
-
-                    discovered.push(Asset {
-                        name,
-                        file_name: path.to_path_buf(),
-                        status: AssetStatus::LOADED,
-                    });
+                    let path = entry.path();
+
+                    if path.is_file() && is_cast_file(path) {
+                        discovered.fetch_add(1, Ordering::Relaxed);
+
+                        if sender.send(path.to_path_buf()).is_err() {
+                            break;
+                        }
+                    }
                 }
-            }
-        }
+            });
 
-        if discovered.is_empty() {
-            return Ok(());
-        }
+            let mut scanned = 0;
 
-        let mut loaded = self.loaded_assets.write();
+            // Wait for the next file, then take whatever else has been found since
+            while let Ok(file_name) = receiver.recv() {
+                if cancel.load(Ordering::Relaxed) {
+                    break;
+                }
 
-        match loaded.as_mut() {
-            Ok(loaded) => {
-                loaded.extend(discovered);
-                Ok(())
+                let batch: Vec<PathBuf> = std::iter::once(file_name)
+                    .chain(receiver.try_iter().take(LOAD_BATCH_SIZE - 1))
+                    .collect();
+
+                scanned += batch.len();
+
+                let assets = Self::scan_files(&settings, batch);
+
+                let mut loaded = self.loaded_assets.write().unwrap();
+                let mut search_assets = self.search_assets.write().unwrap();
+
+                loaded.extend(assets);
+
+                // A search typed while loading should also cover the assets loaded after it
+                *search_assets = self
+                    .last_search
+                    .read()
+                    .unwrap()
+                    .as_ref()
+                    .map(|search| search_results(&loaded, search));
+
+                drop(search_assets);
+                drop(loaded);
+
+                // The walk may still be running, so the total is only what has been found so far
+                let total = discovered.load(Ordering::Relaxed).max(scanned);
+
+                controller.progress_update(false, (scanned * 99 / total) as u32);
             }
-            Err(_) => Err("Failed to acquire write lock on loaded assets".to_string()),
-        }
+
+            // Unblocks the walker if scanning stopped early
+            drop(receiver);
+        });
+
+        Ok(())
     }
 
     /// Exports a game's assets in async.
-    fn export(&self, _settings: Settings, _assets: Vec<usize>, controller: Controller) {
+    fn export(&self, settings: Settings, assets: Vec<usize>, controller: Controller) {
+        // Map the visible indices back to the loaded assets
+        let assets: Vec<usize> = match self.search_assets.read().unwrap().as_ref() {
+            Some(search) => assets
+                .into_iter()
+                .filter_map(|index| search.get(index).copied())
+                .collect(),
+            None => assets,
+        };
+
+        controller.progress_update(false, 0);
+
+        let summary = self.export_assets(&settings, assets, |progress| {
+            controller.progress_update(false, progress)
+        });
+
+        for failure in summary.failures {
+            eprintln!("Failed to export {}: {}", failure.name, failure.error);
+        }
+
         controller.progress_update(true, 100);
     }
 
     /// Loads a game's asset for previewing.
     fn preview(
         &self,
-        _settings: Settings,
+        settings: Settings,
         asset: usize,
         _raw: bool,
         request_id: u64,
@@ -262,48 +1360,292 @@ impl porter_app::AssetManager for AssetManager {
     ) {
         let assets_guard = self.loaded_assets.read().unwrap();
 
-        let (asset_name, asset_ref) = {
-            let search = self.search_assets.read().unwrap();
-            let asset_index = search
-                .as_ref()
-                .and_then(|s| s.get(asset).copied())
-                .unwrap_or(asset);
+        let asset_index = self
+            .search_assets
+            .read()
+            .unwrap()
+            .as_ref()
+            .and_then(|s| s.get(asset).copied())
+            .unwrap_or(asset);
 
-            let selected_asset = &assets_guard[asset_index];
+        // The row may be gone, when watched files were removed since it was picked
+        let Some(asset_ref) = assets_guard.get(asset_index) else {
+            return;
+        };
+
+        *self.previewed_file.write().unwrap() = Some(asset_ref.file_name.clone());
+
+        controller.preview_update(request_id, asset_ref.preview(&settings));
+    }
+
+    fn preview_animation(
+        &self,
+        settings: Settings,
+        asset: usize,
+        animation: PathBuf,
+        request_id: u64,
+        controller: Controller,
+    ) {
+        let assets_guard = self.loaded_assets.read().unwrap();
 
-            let name = PathBuf::from(&selected_asset.name)
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or(&selected_asset.name)
-                .to_string();
+        let asset_index = self
+            .search_assets
+            .read()
+            .unwrap()
+            .as_ref()
+            .and_then(|s| s.get(asset).copied())
+            .unwrap_or(asset);
 
-            (name, selected_asset)
+        let Some(asset_ref) = assets_guard.get(asset_index) else {
+            return;
         };
 
-        let preview_asset = File::open(&asset_ref.file_name).ok().and_then(|mut f| {
-            let mut buffer = Vec::new();
-            if f.read_to_end(&mut buffer).is_err() {
-                return None;
-            }
-            let mut cursor = Cursor::new(&buffer);
-            let file = CastFile::read(&mut cursor).ok()?;
-            let root = file.roots().first()?;
-            let model_node = root.children_of_type(CastId::Model).next()?;
-            // You must NOT return references into `file` or `model_node` here.
-            let model = cast_model::process_model_node(model_node)?;
-            let images = cast_model::load_model_images(&model, &asset_ref.file_name);
-            Some(AssetPreview::Model(asset_name, model, images))
+        *self.previewed_file.write().unwrap() = Some(asset_ref.file_name.clone());
+
+        if asset_ref.asset_type != AssetType::Model {
+            controller.preview_update(request_id, AssetPreview::NotSupported);
+            return;
+        }
+
+        let preview_asset = cast_model::load_model_file(
+            &asset_ref.file_name,
+            asset_ref.root_index,
+            asset_ref.node_index,
+        )
+        .and_then(|mut model| {
+            cast_model::convert_hair_to_meshes(&mut model);
+
+            // The picked file is expected to hold a single animation.
+            let animation = cast_animation::load_animation_file(&animation, 0, 0)?;
+            let resolver =
+                TextureResolver::new(&asset_ref.file_name, settings.texture_directories());
+            let images = cast_model::load_model_images(&model, &resolver);
+
+            Ok(AssetPreview::AnimatedModel(
+                asset_ref.name(),
+                model,
+                images,
+                animation,
+            ))
+        })
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to preview animation {}: {e}", animation.display());
+            AssetPreview::PreviewError
         });
 
-        if let Some(preview) = preview_asset {
-            controller.preview_update(request_id, preview);
+        controller.preview_update(request_id, preview_asset);
+    }
+
+    /// Starts watching the last loaded files or directory when watching is enabled, replacing any previous watcher.
+    fn watch(&self, settings: Settings, controller: Controller) {
+        let mut watcher = self.watcher.lock().unwrap();
+
+        // Dropping the previous watcher stops its thread and waits for it to finish
+        *watcher = None;
+
+        let roots = self.watch_roots.read().unwrap().clone();
+
+        if !settings.watch_files() || roots.is_empty() {
+            return;
+        }
+
+        let loaded_assets = self.loaded_assets.clone();
+        let search_assets = self.search_assets.clone();
+        let last_search = self.last_search.clone();
+        let active_sort = self.active_sort.clone();
+        let previewed_file = self.previewed_file.clone();
+
+        let spawned = FileWatcher::spawn(roots, move |changes| {
+            let moved = Self::apply_file_changes(
+                &settings,
+                &loaded_assets,
+                &search_assets,
+                &last_search,
+                &active_sort,
+                &changes,
+            );
+
+            let preview_changed = previewed_file.read().unwrap().as_ref().is_some_and(|file| {
+                changes.modified.contains(file) || changes.removed.contains(file)
+            });
+
+            controller.assets_changed(changes.removed, moved, preview_changed);
+        });
+
+        match spawned {
+            Ok(spawned) => *watcher = Some(spawned),
+            Err(e) => eprintln!("{e}"),
         }
     }
 
-    /// Cancels an active export.
-    fn export_cancel(&self) {}
+    /// Cancels an active export or directory load.
+    fn export_cancel(&self) {
+        self.export_cancel.store(true, Ordering::Relaxed);
+    }
 
     fn load_game(&self, _settings: Settings) -> Result<(), String> {
         todo!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_cast;
+
+    /// An empty directory for the given test, in the temp directory.
+    fn temp_directory(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("cast_viewer_{name}_{}", std::process::id()));
+
+        let _ = std::fs::remove_dir_all(&directory);
+
+        directory
+    }
+
+    fn asset(name: &str, file_name: &str, node_index: usize) -> Asset {
+        Asset {
+            name: name.to_string(),
+            file_name: PathBuf::from(file_name),
+            asset_type: AssetType::Model,
+            root_index: 0,
+            node_index,
+            material_index: 0,
+            counts: AssetCounts::default(),
+            metadata: Vec::new(),
+            file_size: 0,
+            status: Arc::new(AssetStatus::LOADED),
+        }
+    }
+
+    #[test]
+    fn exports_assets_sharing_a_name_to_their_own_directories() {
+        let assets = [
+            asset("body", "a/hero.cast", 0),
+            asset("Body", "b/hero.cast", 0),
+            asset("body", "b/villain.cast", 1),
+            asset("head", "a/hero.cast", 1),
+        ];
+
+        assert_eq!(
+            export_directory_names(&assets),
+            [
+                "body_hero_0_0",
+                "Body_hero_0_0_1",
+                "body_villain_0_1",
+                "head"
+            ]
+        );
+    }
+
+    #[test]
+    fn removes_every_output_when_a_later_format_fails() {
+        let directory = temp_directory("later_format_fails");
+        let output_directory = directory.join("body");
+
+        let outcome = write_outputs(
+            &output_directory,
+            "body",
+            &[(0, "obj"), (1, "smd")],
+            &AtomicBool::new(false),
+            |file_name, file_type| {
+                std::fs::write(file_name, "partial").unwrap();
+
+                if file_type == 1 {
+                    Err("Failed to export".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+        );
+
+        assert!(outcome.is_err());
+        assert!(!output_directory.exists());
+
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn sorts_assets_added_by_the_watcher() {
+        let directory = temp_directory("sorts_added_assets");
+
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("added.cast"), test_cast::triangle_model()).unwrap();
+
+        let loaded_assets: LoadedAssets = Arc::new(RwLock::new(vec![
+            asset("zeta", "zeta.cast", 0),
+            asset("alpha", "alpha.cast", 0),
+        ]));
+        let active_sort: ActiveSort = Arc::new(RwLock::new(Some(ColumnStatus::new(
+            COLUMN_NAME,
+            Sort::Descending,
+        ))));
+
+        let changes = FileChanges {
+            added: vec![directory.join("added.cast")],
+            removed: Vec::new(),
+            modified: Vec::new(),
+        };
+
+        let moved = AssetManager::apply_file_changes(
+            &Settings::default(),
+            &loaded_assets,
+            &Arc::new(RwLock::new(None)),
+            &Arc::new(RwLock::new(None)),
+            &active_sort,
+            &changes,
+        );
+
+        let names: Vec<String> = loaded_assets
+            .read()
+            .unwrap()
+            .iter()
+            .map(Asset::name)
+            .collect();
+
+        assert_eq!(names.first().map(String::as_str), Some("zeta"));
+        assert_eq!(names.last().map(String::as_str), Some("alpha"));
+        assert!(names.iter().any(|name| name == "triangle"));
+        assert_eq!(moved, [Some(0), Some(names.len() - 1)]);
+
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn exports_materials_without_textures_without_a_directory() {
+        let directory = temp_directory("material_without_textures");
+        let file_name = directory.join("bare.cast");
+
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(
+            &file_name,
+            test_cast::model_file(
+                "bare",
+                &[test_cast::node(
+                    "matl",
+                    0x30,
+                    &[test_cast::string("n", "plain")],
+                    &[],
+                )],
+            ),
+        )
+        .unwrap();
+
+        let mut settings = Settings::default();
+
+        settings.set_output_directory(directory.join("exported"));
+
+        let material = Asset {
+            asset_type: AssetType::Material,
+            file_name,
+            ..asset("bare_plain", "", 0)
+        };
+
+        let outcome = material.export(&settings, "bare_plain", &AtomicBool::new(false));
+
+        assert!(matches!(outcome, Ok(ExportOutcome::Exported)));
+        assert!(!directory.join("exported").join("bare_plain").exists());
+
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+}
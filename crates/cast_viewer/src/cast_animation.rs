@@ -0,0 +1,139 @@
+use porter_animation::{Animation, Curve, CurveAttribute, CurveModeOverride, KeyframeValue};
+use porter_cast::{CastFile, CastId, CastNode};
+use porter_math::Quaternion;
+use std::path::Path;
+
+use crate::cast_model::{integer_values, read_cast_file};
+
+/// Reads the given cast file and processes the animation node at the given root and animation index.
+pub fn load_animation_file(
+    file_name: &Path,
+    root_index: usize,
+    animation_index: usize,
+) -> Result<Animation, String> {
+    let file = read_cast_file(file_name)?;
+
+    let animation_node = find_animation_node(&file, root_index, animation_index)?;
+
+    process_animation_node(animation_node).ok_or_else(|| "Failed to process animation".to_string())
+}
+
+/// Finds the animation node at the given root and animation index.
+pub fn find_animation_node(
+    file: &CastFile,
+    root_index: usize,
+    animation_index: usize,
+) -> Result<&CastNode, String> {
+    file.roots()
+        .get(root_index)
+        .and_then(|root| {
+            root.children_of_type(CastId::Animation)
+                .nth(animation_index)
+        })
+        .ok_or_else(|| format!("No animation {animation_index} found in root {root_index}"))
+}
+
+pub fn process_animation_node(animation_node: &CastNode) -> Option<Animation> {
+    let framerate = animation_node
+        .property("fr")
+        .and_then(|p| p.values::<f32>().next())
+        .unwrap_or(30.0);
+    let looping = animation_node
+        .property("lo")
+        .and_then(|p| p.values::<u8>().next())
+        .map(|v| v != 0)
+        .unwrap_or(false);
+
+    let mut animation = Animation::new(framerate, looping);
+
+    animation.curves = animation_node
+        .children_of_type(CastId::Curve)
+        .filter_map(process_curve_node)
+        .collect();
+
+    for track_node in animation_node.children_of_type(CastId::NotificationTrack) {
+        let name = track_node
+            .property("n")
+            .and_then(|p| p.values::<String>().next())
+            .unwrap_or_default();
+
+        if let Some(key_frames) = track_node.property("kb") {
+            for frame in integer_values(key_frames) {
+                animation.notifications.push((name.clone(), frame));
+            }
+        }
+    }
+
+    Some(animation)
+}
+
+fn process_curve_node(curve_node: &CastNode) -> Option<Curve> {
+    let name = curve_node
+        .property("nn")
+        .and_then(|p| p.values::<String>().next())?;
+    let key_property = curve_node
+        .property("kp")
+        .and_then(|p| p.values::<String>().next())?;
+
+    let mode = curve_node
+        .property("m")
+        .and_then(|p| p.values::<String>().next())
+        .and_then(|mode| match mode.as_str() {
+            "additive" => Some(CurveModeOverride::Additive),
+            "absolute" => Some(CurveModeOverride::Absolute),
+            "relative" => Some(CurveModeOverride::Relative),
+            _ => None,
+        });
+
+    let attribute = match key_property.as_str() {
+        "rq" => CurveAttribute::Rotation,
+        "tx" => CurveAttribute::TranslateX,
+        "ty" => CurveAttribute::TranslateY,
+        "tz" => CurveAttribute::TranslateZ,
+        "sx" => CurveAttribute::ScaleX,
+        "sy" => CurveAttribute::ScaleY,
+        "sz" => CurveAttribute::ScaleZ,
+        "vb" => CurveAttribute::Visibility,
+        _ => {
+            eprintln!("Curve {name}: unsupported key property {key_property}");
+            return None;
+        }
+    };
+
+    let key_frames = curve_node
+        .property("kb")
+        .map(integer_values)
+        .unwrap_or_default();
+    let key_values = curve_node.property("kv")?;
+
+    let values: Vec<KeyframeValue> = match attribute {
+        CurveAttribute::Rotation => key_values
+            .values::<Quaternion>()
+            .map(KeyframeValue::Quaternion)
+            .collect(),
+        CurveAttribute::Visibility => integer_values(key_values)
+            .into_iter()
+            .map(|v| KeyframeValue::Bool(v != 0))
+            .collect(),
+        _ => key_values
+            .values::<f32>()
+            .map(KeyframeValue::Float)
+            .collect(),
+    };
+
+    if key_frames.len() != values.len() {
+        eprintln!(
+            "Curve {name}.{key_property}: {} key frames but {} key values",
+            key_frames.len(),
+            values.len()
+        );
+    }
+
+    let mut curve = Curve::new(name, attribute, mode);
+
+    for (frame, value) in key_frames.into_iter().zip(values) {
+        curve.insert(frame, value);
+    }
+
+    Some(curve)
+}
@@ -1,48 +1,78 @@
-use porter_cast::{CastId, CastNode};
-use porter_math::{Quaternion, Vector2, Vector3};
+use porter_cast::{CastFile, CastId, CastNode, CastProperty, CastPropertyId};
+use porter_math::{Quaternion, Vector2, Vector3, Vector4};
 use porter_model::{
-    Bone, Face, FaceBuffer, Material, MaterialTextureRef, MaterialTextureRefUsage, Mesh, Model,
-    Skeleton, VertexBuffer,
+    BlendShape, Bone, Constraint, ConstraintType, Face, FaceBuffer, Hair, IKHandle, Material,
+    MaterialTextureRef, MaterialTextureRefUsage, Mesh, Model, Skeleton, VertexBuffer, VertexColor,
+    VertexWeight,
 };
-use porter_texture::{Image, ImageFileType};
+use porter_texture::Image;
 use porter_threads::{IntoParallelIterator, ParallelIterator};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{Cursor, Read};
 use std::path::Path;
 
-pub fn load_model_images(model: &Model, file_name: &Path) -> Vec<Option<Image>> {
+use crate::texture_resolver::TextureResolver;
+
+/// Loads the base color texture of every material, for previewing.
+pub fn load_model_images(model: &Model, resolver: &TextureResolver) -> Vec<Option<Image>> {
     model
         .materials
         .as_slice()
         .into_par_iter()
-        .map(|mats| {
-            // Find the first texture with matching usage
-            let texture = mats.base_color_texture();
-            if let Some(images) = texture {
-                let directory = file_name.parent().unwrap_or(Path::new("."));
-                let f = directory.join(&images.file_name);
-                let image_file_type = match f.extension().and_then(|ext| ext.to_str()) {
-                    Some("png") => ImageFileType::Png,
-                    Some("dds") => ImageFileType::Dds,
-                    Some("tiff") => ImageFileType::Tiff,
-                    Some("tga") => ImageFileType::Tga,
-                    _ => {
-                        eprintln!("File has no extension");
-                        ImageFileType::Dds
-                    }
-                };
-                match Image::load(f, image_file_type) {
-                    Ok(image) => Some(image),
-                    Err(err) => {
-                        eprintln!("Failed to load image: {}: {:?}", &images.file_name, err);
-                        None
-                    }
+        .map(|material| {
+            let texture = material.base_color_texture()?;
+
+            // Unresolved textures are reported as issues of the material.
+            let (path, image_file_type) = resolver.resolve(&texture.file_name).ok()?;
+
+            match Image::load(&path, image_file_type) {
+                Ok(image) => Some(image),
+                Err(err) => {
+                    eprintln!("Failed to load image: {}: {:?}", path.display(), err);
+                    None
                 }
-            } else {
-                None
             }
         })
         .collect()
 }
 
+/// Reads the whole cast file at the given path into memory and parses it.
+pub fn read_cast_file(file_name: &Path) -> Result<CastFile, String> {
+    let mut buffer = Vec::new();
+
+    File::open(file_name)
+        .and_then(|mut file| file.read_to_end(&mut buffer))
+        .map_err(|e| format!("Could not open: {e}"))?;
+
+    CastFile::read(&mut Cursor::new(&buffer)).map_err(|e| format!("Error loading file: {e}"))
+}
+
+/// Reads the given cast file and processes the model node at the given root and model index.
+pub fn load_model_file(
+    file_name: &Path,
+    root_index: usize,
+    model_index: usize,
+) -> Result<Model, String> {
+    let file = read_cast_file(file_name)?;
+
+    let model_node = find_model_node(&file, root_index, model_index)?;
+
+    process_model_node(model_node).ok_or_else(|| "Failed to process model".to_string())
+}
+
+/// Finds the model node at the given root and model index.
+pub fn find_model_node(
+    file: &CastFile,
+    root_index: usize,
+    model_index: usize,
+) -> Result<&CastNode, String> {
+    file.roots()
+        .get(root_index)
+        .and_then(|root| root.children_of_type(CastId::Model).nth(model_index))
+        .ok_or_else(|| format!("No model {model_index} found in root {root_index}"))
+}
+
 pub fn process_model_node(model_node: &CastNode) -> Option<Model> {
     let mut model = Model::new();
     model.skeleton = model_node
@@ -52,51 +82,267 @@ pub fn process_model_node(model_node: &CastNode) -> Option<Model> {
         .unwrap_or_else(Skeleton::default);
     process_material_nodes(model_node, &mut model);
     process_mesh_nodes(model_node, &mut model);
+    process_hair_nodes(model_node, &mut model);
     Some(model)
 }
 
+/// The nodes each part of a processed model was read from, in the same order as the model.
+///
+/// Nodes the reader skips, such as ik handles without both ends, aren't listed.
+#[derive(Debug, Default)]
+pub struct ModelSources<'a> {
+    pub skeleton: Option<&'a CastNode>,
+    pub bones: Vec<&'a CastNode>,
+    pub ik_handles: Vec<&'a CastNode>,
+    pub constraints: Vec<&'a CastNode>,
+    pub materials: Vec<&'a CastNode>,
+    pub meshes: Vec<&'a CastNode>,
+    /// The blend shape node and target mesh of every blend shape of each mesh
+    pub blend_shapes: Vec<Vec<(&'a CastNode, &'a CastNode)>>,
+    pub hairs: Vec<&'a CastNode>,
+}
+
+/// Finds the nodes `process_model_node` reads each part of the model from.
+pub fn model_sources(model_node: &CastNode) -> ModelSources<'_> {
+    let mut sources = ModelSources {
+        skeleton: model_node.children_of_type(CastId::Skeleton).next(),
+        materials: model_node.children_of_type(CastId::Material).collect(),
+        hairs: model_node.children_of_type(CastId::Hair).collect(),
+        ..Default::default()
+    };
+
+    if let Some(skeleton_node) = sources.skeleton {
+        sources.bones = skeleton_node.children_of_type(CastId::Bone).collect();
+
+        let bone_indices: HashMap<u64, usize> = sources
+            .bones
+            .iter()
+            .enumerate()
+            .map(|(index, bone_node)| (bone_node.hash(), index))
+            .collect();
+        let resolves =
+            |node: &CastNode, name: &str| bone_reference(node, name, &bone_indices).is_some();
+
+        sources.ik_handles = skeleton_node
+            .children_of_type(CastId::IKHandle)
+            .filter(|node| resolves(node, "sb") && resolves(node, "eb"))
+            .collect();
+        sources.constraints = skeleton_node
+            .children_of_type(CastId::Constraint)
+            .filter(|node| {
+                let constraint_type = node
+                    .property("ct")
+                    .and_then(|p| p.values::<String>().next());
+
+                matches!(constraint_type.as_deref(), Some("pt" | "or" | "sc"))
+                    && resolves(node, "cb")
+                    && resolves(node, "tb")
+            })
+            .collect();
+    }
+
+    let target_hashes: HashSet<u64> = model_node
+        .children_of_type(CastId::BlendShape)
+        .filter_map(|node| node.property("t"))
+        .flat_map(|p| p.values::<u64>().collect::<Vec<_>>())
+        .collect();
+
+    sources.meshes = model_node
+        .children_of_type(CastId::Mesh)
+        .filter(|node| !target_hashes.contains(&node.hash()))
+        .collect();
+    sources.blend_shapes = vec![Vec::new(); sources.meshes.len()];
+
+    for blend_shape_node in model_node.children_of_type(CastId::BlendShape) {
+        let Some(mesh_index) = blend_shape_node
+            .property("b")
+            .and_then(|p| p.values::<u64>().next())
+            .and_then(|hash| sources.meshes.iter().position(|mesh| mesh.hash() == hash))
+        else {
+            continue;
+        };
+
+        let targets = blend_shape_node
+            .property("t")
+            .map(|p| p.values::<u64>().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        for target_hash in targets {
+            if let Some(target_node) = model_node.child_by_hash(target_hash) {
+                sources.blend_shapes[mesh_index].push((blend_shape_node, target_node));
+            }
+        }
+    }
+
+    sources
+}
+
 fn process_skeleton_node(skeleton_node: &CastNode) -> Skeleton {
-    let bones = skeleton_node
+    let (bones, transforms): (Vec<Bone>, Vec<BoneTransforms>) = skeleton_node
         .children_of_type(CastId::Bone)
         .map(process_bone_node)
-        .collect();
+        .unzip();
     let mut skeleton = Skeleton::new();
     skeleton.bones = bones;
+    reconcile_bone_transforms(&mut skeleton.bones, &transforms);
+
+    // IK handles and constraints refer to bones by node hash.
+    let bone_indices: HashMap<u64, usize> = skeleton_node
+        .children_of_type(CastId::Bone)
+        .enumerate()
+        .map(|(index, bone_node)| (bone_node.hash(), index))
+        .collect();
+
+    skeleton.ik_handles = skeleton_node
+        .children_of_type(CastId::IKHandle)
+        .filter_map(|node| process_ik_handle_node(node, &bone_indices))
+        .collect();
+    skeleton.constraints = skeleton_node
+        .children_of_type(CastId::Constraint)
+        .filter_map(|node| process_constraint_node(node, &bone_indices))
+        .collect();
     skeleton
 }
 
-fn process_bone_node(bone_node: &CastNode) -> Bone {
-    let local_position = bone_node
-        .property("lp")
-        .and_then(|p| p.values::<Vector3>().next())
-        .unwrap_or_default();
-    let local_rotation = bone_node
-        .property("lr")
-        .and_then(|p| p.values::<Quaternion>().next())
-        .unwrap_or_default();
-    let local_scale = bone_node
-        .property("s")
-        .and_then(|p| p.values::<Vector3>().next())
-        .unwrap_or_default();
-    let world_position = bone_node
-        .property("wp")
-        .and_then(|p| p.values::<Vector3>().next())
-        .unwrap_or_default();
-    let world_rotation = bone_node
-        .property("wr")
-        .and_then(|p| p.values::<Quaternion>().next())
-        .unwrap_or_default();
-    let world_scale = bone_node
-        .property("s")
-        .and_then(|p| p.values::<Vector3>().next())
-        .unwrap_or_default();
+/// Looks up the bone referenced by the hash stored in the given property.
+fn bone_reference(
+    node: &CastNode,
+    name: &str,
+    bone_indices: &HashMap<u64, usize>,
+) -> Option<usize> {
+    node.property(name)
+        .and_then(|p| p.values::<u64>().next())
+        .and_then(|hash| bone_indices.get(&hash).copied())
+}
+
+fn process_ik_handle_node(
+    ik_handle_node: &CastNode,
+    bone_indices: &HashMap<u64, usize>,
+) -> Option<IKHandle> {
+    let name = ik_handle_node
+        .property("n")
+        .and_then(|p| p.values::<String>().next());
+
+    // A handle without both ends of its chain can't be rebuilt, so it's dropped.
+    let (Some(start_bone), Some(end_bone)) = (
+        bone_reference(ik_handle_node, "sb", bone_indices),
+        bone_reference(ik_handle_node, "eb", bone_indices),
+    ) else {
+        eprintln!(
+            "Skipping ik handle {} with a missing start or end bone",
+            name.as_deref().unwrap_or("<unnamed>")
+        );
+        return None;
+    };
+
+    Some(IKHandle {
+        name,
+        start_bone,
+        end_bone,
+        target_bone: bone_reference(ik_handle_node, "tb", bone_indices),
+        pole_vector_bone: bone_reference(ik_handle_node, "pvb", bone_indices),
+        pole_bone: bone_reference(ik_handle_node, "pb", bone_indices),
+        use_target_rotation: ik_handle_node
+            .property("tr")
+            .and_then(|p| p.values::<u8>().next())
+            .map(|v| v != 0)
+            .unwrap_or(false),
+    })
+}
+
+fn process_constraint_node(
+    constraint_node: &CastNode,
+    bone_indices: &HashMap<u64, usize>,
+) -> Option<Constraint> {
+    let name = constraint_node
+        .property("n")
+        .and_then(|p| p.values::<String>().next());
+    let flag = |property: &str| {
+        constraint_node
+            .property(property)
+            .and_then(|p| p.values::<u8>().next())
+            .map(|v| v != 0)
+            .unwrap_or(false)
+    };
+
+    let constraint_type = match constraint_node
+        .property("ct")
+        .and_then(|p| p.values::<String>().next())
+        .as_deref()
+    {
+        Some("pt") => ConstraintType::Point,
+        Some("or") => ConstraintType::Orient,
+        Some("sc") => ConstraintType::Scale,
+        other => {
+            eprintln!(
+                "Skipping constraint {} with unknown type {other:?}",
+                name.as_deref().unwrap_or("<unnamed>")
+            );
+            return None;
+        }
+    };
+
+    let (Some(constraint_bone), Some(target_bone)) = (
+        bone_reference(constraint_node, "cb", bone_indices),
+        bone_reference(constraint_node, "tb", bone_indices),
+    ) else {
+        eprintln!(
+            "Skipping constraint {} with a missing constraint or target bone",
+            name.as_deref().unwrap_or("<unnamed>")
+        );
+        return None;
+    };
+
+    Some(Constraint {
+        name,
+        constraint_type,
+        constraint_bone,
+        target_bone,
+        maintain_offset: flag("mo"),
+        skip_x: flag("sx"),
+        skip_y: flag("sy"),
+        skip_z: flag("sz"),
+    })
+}
+
+/// Which transforms a bone node provided.
+#[derive(Debug, Clone, Copy)]
+struct BoneTransforms {
+    local: bool,
+    world: bool,
+}
+
+fn process_bone_node(bone_node: &CastNode) -> (Bone, BoneTransforms) {
+    let vector = |name: &str| {
+        bone_node
+            .property(name)
+            .and_then(|p| p.values::<Vector3>().next())
+    };
+    let rotation = |name: &str| {
+        bone_node
+            .property(name)
+            .and_then(|p| p.values::<Quaternion>().next())
+    };
+
+    let local_position = vector("lp");
+    let local_rotation = rotation("lr");
+    let world_position = vector("wp");
+    let world_rotation = rotation("wr");
+
+    let transforms = BoneTransforms {
+        local: local_position.is_some() || local_rotation.is_some(),
+        world: world_position.is_some() || world_rotation.is_some(),
+    };
+
+    // Cast only stores the local scale, the world scale is derived from the parents.
+    let local_scale = vector("s").unwrap_or(Vector3::new(1.0, 1.0, 1.0));
     let segment_scale_compensate = bone_node
         .property("ssc")
         .and_then(|p| p.values::<u8>().next())
         .map(|v| v != 0)
         .unwrap_or(false);
 
-    Bone {
+    let bone = Bone {
         name: bone_node
             .property("n")
             .and_then(|p| p.values::<String>().next()),
@@ -105,62 +351,319 @@ fn process_bone_node(bone_node: &CastNode) -> Bone {
             .and_then(|p| p.values::<u32>().next())
             .map(|v| v as i32)
             .unwrap_or(-1),
-        local_position,
-        local_rotation,
+        local_position: local_position.unwrap_or_default(),
+        local_rotation: local_rotation.unwrap_or(IDENTITY_ROTATION),
         local_scale,
-        world_position,
-        world_rotation,
-        world_scale,
+        world_position: world_position.unwrap_or_default(),
+        world_rotation: world_rotation.unwrap_or(IDENTITY_ROTATION),
+        world_scale: local_scale,
         segment_scale_compensate,
-    }
+    };
+
+    (bone, transforms)
 }
 
-fn process_material_nodes(model_node: &CastNode, model: &mut Model) {
-    let new_materials: Vec<Material> = model_node
-        .children_of_type(CastId::Material)
-        .map(|child_node| {
-            let name = child_node
-                .property("n")
-                .and_then(|p| p.values::<String>().next())
-                .unwrap_or_default();
+const IDENTITY_ROTATION: Quaternion = Quaternion {
+    x: 0.0,
+    y: 0.0,
+    z: 0.0,
+    w: 1.0,
+};
 
-            let mut material = Material::new(name);
+/// Derives the world transforms of bones that only have local transforms, the local transforms of bones that
+/// only have world transforms, and the world scale of every bone, walking each parent chain once.
+///
+/// Bones with segment scale compensation don't inherit the local scale of their direct parent, like in Maya,
+/// but still inherit the scale of every bone above it, and their position is still scaled by the parent.
+fn reconcile_bone_transforms(bones: &mut [Bone], transforms: &[BoneTransforms]) {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Pending,
+        Visiting,
+        Done,
+    }
 
-            let albedo_hash = child_node
-                .property("albedo")
-                .and_then(|p| p.values::<u64>().next())
-                .or_else(|| {
-                    child_node
-                        .property("diffuse")
-                        .and_then(|p| p.values::<u64>().next())
-                })
-                .unwrap_or(0);
+    fn resolve(
+        bones: &mut [Bone],
+        transforms: &[BoneTransforms],
+        states: &mut [State],
+        index: usize,
+    ) {
+        if states[index] != State::Pending {
+            return;
+        }
 
-            if let Some(albedo) = child_node.child_by_hash(albedo_hash) {
-                let file_name = albedo
-                    .property("p")
-                    .and_then(|p| p.values::<String>().next())
-                    .unwrap_or_default();
+        states[index] = State::Visiting;
+
+        let parent = usize::try_from(bones[index].parent)
+            .ok()
+            .filter(|&parent| parent < bones.len() && parent != index);
+
+        if let Some(parent) = parent {
+            resolve(bones, transforms, states, parent);
+        }
+
+        // A parent still being visited means the chain loops back here, so treat this bone as a root.
+        let parent = parent
+            .filter(|&parent| states[parent] == State::Done)
+            .map(|parent| {
+                let parent = &bones[parent];
+
+                (
+                    parent.world_position,
+                    parent.world_rotation,
+                    parent.world_scale,
+                    parent.local_scale,
+                )
+            });
+
+        let bone = &mut bones[index];
+        let BoneTransforms { local, world } = transforms[index];
+
+        match parent {
+            Some((parent_position, parent_rotation, parent_scale, parent_local_scale)) => {
+                if world && !local {
+                    let inverse = conjugate(parent_rotation);
+                    let offset = rotate(
+                        inverse,
+                        Vector3::new(
+                            bone.world_position.x - parent_position.x,
+                            bone.world_position.y - parent_position.y,
+                            bone.world_position.z - parent_position.z,
+                        ),
+                    );
 
-                let texture_ref = MaterialTextureRef {
-                    file_name: file_name.to_string(),
-                    texture_usage: MaterialTextureRefUsage::Albedo,
-                    texture_alias: "".to_string(),
-                    texture_hash: albedo_hash,
+                    bone.local_position = Vector3::new(
+                        divide(offset.x, parent_scale.x),
+                        divide(offset.y, parent_scale.y),
+                        divide(offset.z, parent_scale.z),
+                    );
+                    bone.local_rotation = multiply(inverse, bone.world_rotation);
+                } else if !world {
+                    let offset = rotate(
+                        parent_rotation,
+                        Vector3::new(
+                            bone.local_position.x * parent_scale.x,
+                            bone.local_position.y * parent_scale.y,
+                            bone.local_position.z * parent_scale.z,
+                        ),
+                    );
+
+                    bone.world_position = Vector3::new(
+                        parent_position.x + offset.x,
+                        parent_position.y + offset.y,
+                        parent_position.z + offset.z,
+                    );
+                    bone.world_rotation = multiply(parent_rotation, bone.local_rotation);
+                }
+
+                bone.world_scale = if bone.segment_scale_compensate {
+                    Vector3::new(
+                        divide(bone.local_scale.x * parent_scale.x, parent_local_scale.x),
+                        divide(bone.local_scale.y * parent_scale.y, parent_local_scale.y),
+                        divide(bone.local_scale.z * parent_scale.z, parent_local_scale.z),
+                    )
+                } else {
+                    Vector3::new(
+                        bone.local_scale.x * parent_scale.x,
+                        bone.local_scale.y * parent_scale.y,
+                        bone.local_scale.z * parent_scale.z,
+                    )
                 };
-                material.textures.push(texture_ref);
             }
+            None => {
+                if world && !local {
+                    bone.local_position = bone.world_position;
+                    bone.local_rotation = bone.world_rotation;
+                } else if !world {
+                    bone.world_position = bone.local_position;
+                    bone.world_rotation = bone.local_rotation;
+                }
 
-            material
-        })
+                bone.world_scale = bone.local_scale;
+            }
+        }
+
+        states[index] = State::Done;
+    }
+
+    let mut states = vec![State::Pending; bones.len()];
+
+    for index in 0..bones.len() {
+        resolve(bones, transforms, &mut states, index);
+    }
+}
+
+/// Hamilton product of two quaternions, applying `b` first.
+fn multiply(a: Quaternion, b: Quaternion) -> Quaternion {
+    Quaternion {
+        x: a.w * b.x + a.x * b.w + a.y * b.z - a.z * b.y,
+        y: a.w * b.y - a.x * b.z + a.y * b.w + a.z * b.x,
+        z: a.w * b.z + a.x * b.y - a.y * b.x + a.z * b.w,
+        w: a.w * b.w - a.x * b.x - a.y * b.y - a.z * b.z,
+    }
+}
+
+/// Inverse of a unit quaternion.
+fn conjugate(q: Quaternion) -> Quaternion {
+    Quaternion {
+        x: -q.x,
+        y: -q.y,
+        z: -q.z,
+        w: q.w,
+    }
+}
+
+/// Rotates a vector by a unit quaternion.
+fn rotate(q: Quaternion, v: Vector3) -> Vector3 {
+    let rotated = multiply(
+        multiply(
+            q,
+            Quaternion {
+                x: v.x,
+                y: v.y,
+                z: v.z,
+                w: 0.0,
+            },
+        ),
+        conjugate(q),
+    );
+
+    Vector3::new(rotated.x, rotated.y, rotated.z)
+}
+
+/// Divides by a scale component, leaving the value alone when the scale collapsed to zero.
+fn divide(value: f32, scale: f32) -> f32 {
+    if scale.abs() > f32::EPSILON {
+        value / scale
+    } else {
+        value
+    }
+}
+
+/// Material slots defined by the cast format, and the texture usage they map to.
+pub const MATERIAL_SLOTS: &[(&str, MaterialTextureRefUsage)] = &[
+    ("albedo", MaterialTextureRefUsage::Albedo),
+    ("diffuse", MaterialTextureRefUsage::Diffuse),
+    ("normal", MaterialTextureRefUsage::Normal),
+    ("specular", MaterialTextureRefUsage::Specular),
+    ("gloss", MaterialTextureRefUsage::Gloss),
+    ("roughness", MaterialTextureRefUsage::Roughness),
+    ("metal", MaterialTextureRefUsage::Metalness),
+    ("emissive", MaterialTextureRefUsage::Emissive),
+    ("ao", MaterialTextureRefUsage::AmbientOcclusion),
+    ("cavity", MaterialTextureRefUsage::Cavity),
+];
+
+fn process_material_nodes(model_node: &CastNode, model: &mut Model) {
+    let new_materials: Vec<Material> = model_node
+        .children_of_type(CastId::Material)
+        .map(process_material_node)
         .collect();
 
     model.materials.extend(new_materials);
 }
 
+/// Processes a material node and the texture files of its slots.
+pub fn process_material_node(material_node: &CastNode) -> Material {
+    let name = material_node
+        .property("n")
+        .and_then(|p| p.values::<String>().next())
+        .unwrap_or_default();
+
+    let mut material = Material::new(name);
+
+    let slots = MATERIAL_SLOTS
+        .iter()
+        .map(|(slot, usage)| (slot.to_string(), *usage));
+
+    // Extra slots are numbered from zero, and hold anything without a dedicated slot.
+    let extra_slots = (0..)
+        .map(|index| format!("extra{index}"))
+        .take_while(|slot| material_node.property(slot).is_some())
+        .map(|slot| (slot, MaterialTextureRefUsage::Unknown));
+
+    for (slot, texture_usage) in slots.chain(extra_slots) {
+        let Some(texture_hash) = material_node
+            .property(&slot)
+            .and_then(|p| p.values::<u64>().next())
+        else {
+            continue;
+        };
+
+        let Some(file_node) = material_node.child_by_hash(texture_hash) else {
+            eprintln!(
+                "Material {}: slot {slot} references missing file node {texture_hash:#X}",
+                material.name
+            );
+            continue;
+        };
+
+        let file_name = file_node
+            .property("p")
+            .and_then(|p| p.values::<String>().next())
+            .unwrap_or_default();
+
+        material.textures.push(MaterialTextureRef {
+            file_name,
+            texture_usage,
+            texture_alias: slot,
+            texture_hash,
+        });
+    }
+
+    material
+}
+
+/// Finds the material node at the given index among the materials of a model node.
+pub fn find_material_node(
+    model_node: &CastNode,
+    material_index: usize,
+) -> Result<&CastNode, String> {
+    model_node
+        .children_of_type(CastId::Material)
+        .nth(material_index)
+        .ok_or_else(|| format!("No material {material_index} found in model"))
+}
+
+/// Loads the image of every texture slot of the material that can be resolved, along with why the others can't.
+pub fn load_material_images(
+    material: &Material,
+    resolver: &TextureResolver,
+) -> (Vec<(MaterialTextureRefUsage, Image)>, Vec<(String, String)>) {
+    let mut images = Vec::with_capacity(material.textures.len());
+    let mut unresolved = Vec::new();
+
+    for texture in &material.textures {
+        let image = resolver
+            .resolve(&texture.file_name)
+            .and_then(|(path, image_file_type)| {
+                Image::load(&path, image_file_type)
+                    .map_err(|e| format!("{}: {e:?}", path.display()))
+            });
+
+        match image {
+            Ok(image) => images.push((texture.texture_usage, image)),
+            Err(e) => unresolved.push((texture.texture_alias.clone(), e)),
+        }
+    }
+
+    (images, unresolved)
+}
+
 fn process_mesh_nodes(model_node: &CastNode, model: &mut Model) {
+    // Blend shape targets are stored as meshes, but only their positions are used.
+    let target_hashes: HashSet<u64> = model_node
+        .children_of_type(CastId::BlendShape)
+        .filter_map(|node| node.property("t"))
+        .flat_map(|p| p.values::<u64>().collect::<Vec<_>>())
+        .collect();
+
     // Gather all mesh nodes first
-    let mesh_nodes: Vec<&CastNode> = model_node.children_of_type(CastId::Mesh).collect();
+    let mesh_nodes: Vec<&CastNode> = model_node
+        .children_of_type(CastId::Mesh)
+        .filter(|node| !target_hashes.contains(&node.hash()))
+        .collect();
 
     let meshes: Vec<Mesh> = mesh_nodes
         .as_slice()
@@ -168,12 +671,24 @@ fn process_mesh_nodes(model_node: &CastNode, model: &mut Model) {
         .map(|child_node| {
             let uv_layers = child_node
                 .property("ul")
-                .and_then(|p| p.values::<u32>().next())
+                .and_then(|p| integer_values(p).first().copied())
                 .unwrap_or(0);
 
+            let color_layers = child_node
+                .property("cl")
+                .and_then(|p| integer_values(p).first().copied())
+                .unwrap_or_else(|| {
+                    // Older files only carry a single color layer without a count.
+                    (0..)
+                        .take_while(|layer| child_node.property(&format!("c{layer}")).is_some())
+                        .count()
+                        .max(child_node.property("vc").is_some() as usize)
+                        as u32
+                });
+
             let weight_influence = child_node
                 .property("mi")
-                .and_then(|p| p.values::<u32>().next())
+                .and_then(|p| integer_values(p).first().copied())
                 .unwrap_or(0);
 
             //This may be the worst thing I've ever seen???
@@ -188,8 +703,12 @@ fn process_mesh_nodes(model_node: &CastNode, model: &mut Model) {
                 })
                 .and_then(|mat_name| model.materials.iter().position(|mat| mat.name == mat_name));
 
+            let name = child_node
+                .property("n")
+                .and_then(|p| p.values::<String>().next());
+
             let mut vertex_buffer = VertexBuffer::builder()
-                .colors(0)
+                .colors(color_layers as usize)
                 .uv_layers(uv_layers as usize)
                 .maximum_influence(weight_influence as usize)
                 .build();
@@ -201,42 +720,711 @@ fn process_mesh_nodes(model_node: &CastNode, model: &mut Model) {
                 }
             }
 
+            // Layers longer than the positions are broken, their extra values are ignored.
+            let vertex_count = vertex_buffer.len();
+
             // Normals
             if let Some(vn_property) = child_node.property("vn") {
-                for (i, n) in vn_property.values::<Vector3>().enumerate() {
+                for (i, n) in vn_property
+                    .values::<Vector3>()
+                    .take(vertex_count)
+                    .enumerate()
+                {
                     vertex_buffer.vertex_mut(i).set_normal(n);
                 }
             }
 
-            // UV0
-            if let Some(uv0_property) = child_node.property("u0") {
-                for (i, uv) in uv0_property.values::<Vector2>().enumerate() {
-                    vertex_buffer.vertex_mut(i).set_uv(0, uv);
+            // UV Layers
+            for layer in 0..uv_layers as usize {
+                if let Some(uv_property) = child_node.property(&format!("u{layer}")) {
+                    for (i, uv) in uv_property
+                        .values::<Vector2>()
+                        .take(vertex_count)
+                        .enumerate()
+                    {
+                        vertex_buffer.vertex_mut(i).set_uv(layer, uv);
+                    }
                 }
             }
 
-            // UV1
-            if let Some(uv1_property) = child_node.property("u1") {
-                for (i, uv) in uv1_property.values::<Vector2>().enumerate() {
-                    vertex_buffer.vertex_mut(i).set_uv(1, uv);
+            // Color Layers
+            for layer in 0..color_layers as usize {
+                let color_property = child_node
+                    .property(&format!("c{layer}"))
+                    .or_else(|| (layer == 0).then(|| child_node.property("vc")).flatten());
+
+                if let Some(color_property) = color_property {
+                    for (i, color) in color_values(color_property)
+                        .into_iter()
+                        .take(vertex_count)
+                        .enumerate()
+                    {
+                        vertex_buffer.vertex_mut(i).set_color(layer, color);
+                    }
                 }
             }
 
+            // Weights
+            if weight_influence > 0 {
+                let weight_bones = child_node
+                    .property("wb")
+                    .map(integer_values)
+                    .unwrap_or_default();
+                let weight_values: Vec<f32> = child_node
+                    .property("wv")
+                    .map(|p| p.values::<f32>().collect())
+                    .unwrap_or_default();
+
+                process_mesh_weights(
+                    &mut vertex_buffer,
+                    weight_influence as usize,
+                    &weight_bones,
+                    &weight_values,
+                    model.skeleton.bones.len(),
+                    name.as_deref().unwrap_or("<unnamed>"),
+                );
+            }
+
             // Faces
             let mut face_buffer = FaceBuffer::new();
             if let Some(f_property) = child_node.property("f") {
-                let indices: Vec<u32> = f_property.values::<u32>().collect();
-                for chunk in indices.chunks_exact(3) {
+                let indices = integer_values(f_property);
+
+                // Faces pointing past the vertices are dropped.
+                for chunk in indices
+                    .chunks_exact(3)
+                    .filter(|chunk| chunk.iter().all(|index| (*index as usize) < vertex_count))
+                {
                     face_buffer.push(Face::new(chunk[2], chunk[1], chunk[0]));
                 }
             }
 
             Mesh {
+                name,
                 material: material_index,
                 ..Mesh::new(face_buffer, vertex_buffer)
             }
         })
         .collect();
 
+    let mesh_offset = model.meshes.len();
+
     model.meshes.extend(meshes);
+
+    let mesh_hashes: Vec<u64> = mesh_nodes.iter().map(|node| node.hash()).collect();
+
+    process_blend_shape_nodes(model_node, &mesh_hashes, mesh_offset, model);
+}
+
+/// Number of sides of the tubes hair strands are converted to.
+const HAIR_TUBE_SIDES: u32 = 3;
+/// Radius of the tubes hair strands are converted to.
+const HAIR_TUBE_RADIUS: f32 = 0.025;
+
+fn process_hair_nodes(model_node: &CastNode, model: &mut Model) {
+    for hair_node in model_node.children_of_type(CastId::Hair) {
+        let name = hair_node
+            .property("n")
+            .and_then(|p| p.values::<String>().next());
+
+        let material = hair_node
+            .property("m")
+            .and_then(|p| p.values::<u64>().next())
+            .and_then(|hash| model_node.child_by_hash(hash))
+            .and_then(|mat_node| {
+                mat_node
+                    .property("n")
+                    .and_then(|p| p.values::<String>().next())
+            })
+            .and_then(|mat_name| model.materials.iter().position(|mat| mat.name == mat_name));
+
+        let segments = hair_node
+            .property("se")
+            .map(integer_values)
+            .unwrap_or_default();
+        let particles: Vec<Vector3> = hair_node
+            .property("pt")
+            .map(|p| p.values::<Vector3>().collect())
+            .unwrap_or_default();
+
+        // Every strand has one more particle than segments.
+        let required: usize = segments.iter().map(|count| *count as usize + 1).sum();
+
+        if required != particles.len() {
+            eprintln!(
+                "Hair {}: {} particles for strands needing {required}",
+                name.as_deref().unwrap_or("<unnamed>"),
+                particles.len()
+            );
+        }
+
+        model.hairs.push(Hair {
+            name,
+            segments,
+            particles,
+            material,
+        });
+    }
+}
+
+/// Replaces the hair of the model with tube meshes, one mesh per hair, for renderers and formats without hair.
+pub fn convert_hair_to_meshes(model: &mut Model) {
+    for hair in std::mem::take(&mut model.hairs) {
+        let mut vertex_buffer = VertexBuffer::builder().build();
+        let mut face_buffer = FaceBuffer::new();
+
+        let mut start = 0;
+
+        for segments in &hair.segments {
+            let end = start + *segments as usize + 1;
+
+            // Strands running past the particles are broken, the rest of the hair is skipped.
+            let Some(strand) = hair.particles.get(start..end) else {
+                break;
+            };
+
+            start = end;
+
+            if strand.len() < 2 {
+                continue;
+            }
+
+            let first_ring = vertex_buffer.len() as u32;
+
+            for (index, position) in strand.iter().enumerate() {
+                let (from, to) = if index + 1 < strand.len() {
+                    (*position, strand[index + 1])
+                } else {
+                    (strand[index - 1], *position)
+                };
+
+                let (side, up) =
+                    perpendicular_axes(Vector3::new(to.x - from.x, to.y - from.y, to.z - from.z));
+
+                for corner in 0..HAIR_TUBE_SIDES {
+                    let angle = corner as f32 / HAIR_TUBE_SIDES as f32 * std::f32::consts::TAU;
+                    let normal = Vector3::new(
+                        side.x * angle.cos() + up.x * angle.sin(),
+                        side.y * angle.cos() + up.y * angle.sin(),
+                        side.z * angle.cos() + up.z * angle.sin(),
+                    );
+
+                    let mut vertex = vertex_buffer.create();
+
+                    vertex.set_position(Vector3::new(
+                        position.x + normal.x * HAIR_TUBE_RADIUS,
+                        position.y + normal.y * HAIR_TUBE_RADIUS,
+                        position.z + normal.z * HAIR_TUBE_RADIUS,
+                    ));
+                    vertex.set_normal(normal);
+                }
+            }
+
+            for ring in 0..strand.len() as u32 - 1 {
+                let current = first_ring + ring * HAIR_TUBE_SIDES;
+                let next = current + HAIR_TUBE_SIDES;
+
+                for corner in 0..HAIR_TUBE_SIDES {
+                    let following = (corner + 1) % HAIR_TUBE_SIDES;
+
+                    face_buffer.push(Face::new(
+                        current + corner,
+                        next + corner,
+                        current + following,
+                    ));
+                    face_buffer.push(Face::new(
+                        current + following,
+                        next + corner,
+                        next + following,
+                    ));
+                }
+            }
+        }
+
+        if face_buffer.is_empty() {
+            continue;
+        }
+
+        model.meshes.push(Mesh {
+            name: hair.name,
+            material: hair.material,
+            ..Mesh::new(face_buffer, vertex_buffer)
+        });
+    }
+}
+
+/// Two unit vectors perpendicular to the given direction and to each other.
+fn perpendicular_axes(direction: Vector3) -> (Vector3, Vector3) {
+    let normalize = |v: Vector3| {
+        let length = (v.x * v.x + v.y * v.y + v.z * v.z).sqrt();
+
+        if length > f32::EPSILON {
+            Vector3::new(v.x / length, v.y / length, v.z / length)
+        } else {
+            Vector3::new(0.0, 0.0, 1.0)
+        }
+    };
+    let cross = |a: Vector3, b: Vector3| {
+        Vector3::new(
+            a.y * b.z - a.z * b.y,
+            a.z * b.x - a.x * b.z,
+            a.x * b.y - a.y * b.x,
+        )
+    };
+
+    let direction = normalize(direction);
+
+    // Cross with the axis the direction is least aligned with, so the result doesn't collapse.
+    let axis = if direction.x.abs() < 0.9 {
+        Vector3::new(1.0, 0.0, 0.0)
+    } else {
+        Vector3::new(0.0, 1.0, 0.0)
+    };
+
+    let side = normalize(cross(direction, axis));
+    let up = cross(direction, side);
+
+    (side, up)
+}
+
+fn process_blend_shape_nodes(
+    model_node: &CastNode,
+    mesh_hashes: &[u64],
+    mesh_offset: usize,
+    model: &mut Model,
+) {
+    for blend_shape_node in model_node.children_of_type(CastId::BlendShape) {
+        let name = blend_shape_node
+            .property("n")
+            .and_then(|p| p.values::<String>().next())
+            .unwrap_or_else(|| "<unnamed>".to_string());
+
+        let Some(base_hash) = blend_shape_node
+            .property("b")
+            .and_then(|p| p.values::<u64>().next())
+        else {
+            eprintln!("Blend shape {name}: no base shape");
+            continue;
+        };
+
+        let Some(mesh_index) = mesh_hashes.iter().position(|hash| *hash == base_hash) else {
+            eprintln!("Blend shape {name}: base shape {base_hash:#X} is not a mesh of this model");
+            continue;
+        };
+
+        let target_hashes: Vec<u64> = blend_shape_node
+            .property("t")
+            .map(|p| p.values::<u64>().collect())
+            .unwrap_or_default();
+        let target_scales: Vec<f32> = blend_shape_node
+            .property("ts")
+            .map(|p| p.values::<f32>().collect())
+            .unwrap_or_default();
+
+        let mesh = &mut model.meshes[mesh_offset + mesh_index];
+
+        for (index, target_hash) in target_hashes.into_iter().enumerate() {
+            let Some(target_node) = model_node.child_by_hash(target_hash) else {
+                eprintln!("Blend shape {name}: target shape {target_hash:#X} is missing");
+                continue;
+            };
+
+            let target_name = target_node
+                .property("n")
+                .and_then(|p| p.values::<String>().next())
+                .unwrap_or_else(|| format!("{name}_{index}"));
+
+            let target_positions: Vec<Vector3> = target_node
+                .property("vp")
+                .map(|p| p.values::<Vector3>().collect())
+                .unwrap_or_default();
+
+            if target_positions.len() != mesh.vertices.len() {
+                eprintln!(
+                    "Blend shape {name}: target {target_name} has {} vertices but the base shape has {}",
+                    target_positions.len(),
+                    mesh.vertices.len()
+                );
+            }
+
+            let mut blend_shape = BlendShape::new(
+                target_name,
+                target_scales.get(index).copied().unwrap_or(1.0),
+            );
+
+            // Only vertices that actually move are kept as deltas.
+            for (i, target) in target_positions
+                .into_iter()
+                .take(mesh.vertices.len())
+                .enumerate()
+            {
+                let base = mesh.vertices.vertex(i).position();
+                let delta = Vector3::new(target.x - base.x, target.y - base.y, target.z - base.z);
+
+                if delta.x.abs() > f32::EPSILON
+                    || delta.y.abs() > f32::EPSILON
+                    || delta.z.abs() > f32::EPSILON
+                {
+                    blend_shape.vertex_deltas.insert(i as u32, delta);
+                }
+            }
+
+            mesh.blend_shapes.push(blend_shape);
+        }
+    }
+}
+
+fn process_mesh_weights(
+    vertex_buffer: &mut VertexBuffer,
+    maximum_influence: usize,
+    weight_bones: &[u32],
+    weight_values: &[f32],
+    bone_count: usize,
+    mesh_name: &str,
+) {
+    let vertex_count = vertex_buffer.len();
+    let expected = vertex_count * maximum_influence;
+
+    if weight_bones.len() < expected || weight_values.len() < expected {
+        eprintln!(
+            "Mesh {mesh_name}: expected {expected} weights, found {} bones and {} values",
+            weight_bones.len(),
+            weight_values.len()
+        );
+    }
+
+    let mut invalid_bones = 0;
+    let mut unnormalized = 0;
+
+    let weights = weight_bones
+        .chunks_exact(maximum_influence)
+        .zip(weight_values.chunks_exact(maximum_influence))
+        .take(vertex_count);
+
+    for (i, (bones, values)) in weights.enumerate() {
+        let mut sum = 0.0;
+
+        for (j, (&bone, &value)) in bones.iter().zip(values).enumerate() {
+            if bone as usize >= bone_count {
+                if value > 0.0 {
+                    invalid_bones += 1;
+                }
+
+                vertex_buffer
+                    .vertex_mut(i)
+                    .set_weight(j, VertexWeight::new(0, 0.0));
+                continue;
+            }
+
+            sum += value;
+
+            vertex_buffer
+                .vertex_mut(i)
+                .set_weight(j, VertexWeight::new(bone as u16, value));
+        }
+
+        if (sum - 1.0f32).abs() > 0.001 {
+            unnormalized += 1;
+        }
+    }
+
+    if invalid_bones > 0 {
+        eprintln!(
+            "Mesh {mesh_name}: {invalid_bones} weights reference bones outside the skeleton ({bone_count} bones)"
+        );
+    }
+
+    if unnormalized > 0 {
+        eprintln!("Mesh {mesh_name}: {unnormalized} vertices have weights that don't sum to 1");
+    }
+}
+
+/// Reads an integer array property, regardless of the width it was stored with.
+pub fn integer_values(property: &CastProperty) -> Vec<u32> {
+    match property.id() {
+        CastPropertyId::Byte => property.values::<u8>().map(u32::from).collect(),
+        CastPropertyId::Short => property.values::<u16>().map(u32::from).collect(),
+        _ => property.values::<u32>().collect(),
+    }
+}
+
+/// Reads a color layer property, stored either as packed RGBA or as float4 values.
+pub fn color_values(property: &CastProperty) -> Vec<VertexColor> {
+    match property.id() {
+        CastPropertyId::Vector4 => property
+            .values::<Vector4>()
+            .map(|color| {
+                let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+                VertexColor::new(
+                    channel(color.x),
+                    channel(color.y),
+                    channel(color.z),
+                    channel(color.w),
+                )
+            })
+            .collect(),
+        _ => property
+            .values::<u32>()
+            .map(|color| {
+                let [r, g, b, a] = color.to_le_bytes();
+
+                VertexColor::new(r, g, b, a)
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_cast;
+    use std::f32::consts::FRAC_1_SQRT_2;
+
+    fn read_model(buffer: &[u8]) -> Model {
+        let file = CastFile::read(&mut Cursor::new(buffer)).unwrap();
+
+        process_model_node(find_model_node(&file, 0, 0).unwrap()).unwrap()
+    }
+
+    /// A model with a triangle weighted to two bones, storing the weight bones with the given property.
+    fn weighted_model(weight_bones: Vec<u8>) -> Model {
+        let mesh = test_cast::node(
+            "mesh",
+            0x40,
+            &[
+                test_cast::vectors("vp", &[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]),
+                test_cast::bytes("mi", &[2]),
+                weight_bones,
+                test_cast::floats("wv", &[1.0, 0.0, 0.5, 0.5, 1.0, 0.0]),
+                test_cast::integers("f", &[0, 1, 2]),
+            ],
+            &[],
+        );
+
+        read_model(&test_cast::model_file(
+            "weighted",
+            &[test_cast::two_bone_skeleton(), mesh],
+        ))
+    }
+
+    fn assert_near(actual: Vector3, expected: [f32; 3]) {
+        let close = [actual.x, actual.y, actual.z]
+            .iter()
+            .zip(expected)
+            .all(|(actual, expected)| (actual - expected).abs() < 1e-5);
+
+        assert!(close, "{actual:?} is not {expected:?}");
+    }
+
+    #[test]
+    fn reads_triangle_model() {
+        let model = read_model(&test_cast::triangle_model());
+
+        assert_eq!(model.skeleton.bones.len(), 2);
+        assert_eq!(model.materials.len(), 1);
+        assert_eq!(model.meshes.len(), 1);
+
+        let mesh = &model.meshes[0];
+
+        assert_eq!(mesh.name.as_deref(), Some("body"));
+        assert_eq!(mesh.material, Some(0));
+        assert_eq!(mesh.vertices.len(), 3);
+        assert_eq!(mesh.faces.len(), 1);
+
+        let uv = mesh.vertices.vertex(1).uv(0);
+
+        assert_eq!((uv.x, uv.y), (1.0, 0.0));
+
+        let color = mesh.vertices.vertex(0).color(0);
+
+        assert_eq!([color.r, color.g, color.b, color.a], [0xFF, 0, 0, 0xFF]);
+    }
+
+    #[test]
+    fn reads_weights_of_every_bone_width() {
+        let shorts: Vec<u8> = [0u16, 1, 0, 1, 1, 0]
+            .iter()
+            .flat_map(|bone| bone.to_le_bytes())
+            .collect();
+
+        for weight_bones in [
+            test_cast::bytes("wb", &[0, 1, 0, 1, 1, 0]),
+            test_cast::property("h", "wb", 6, &shorts),
+            test_cast::integers("wb", &[0, 1, 0, 1, 1, 0]),
+        ] {
+            let model = weighted_model(weight_bones);
+            let vertices = &model.meshes[0].vertices;
+
+            let weights: Vec<(u16, f32)> = (0..vertices.len())
+                .flat_map(|vertex| (0..2).map(move |influence| (vertex, influence)))
+                .map(|(vertex, influence)| {
+                    let weight = vertices.vertex(vertex).weight(influence);
+
+                    (weight.bone, weight.value)
+                })
+                .collect();
+
+            assert_eq!(
+                weights,
+                [(0, 1.0), (1, 0.0), (0, 0.5), (1, 0.5), (1, 1.0), (0, 0.0)]
+            );
+        }
+    }
+
+    #[test]
+    fn drops_weights_of_missing_bones() {
+        let model = weighted_model(test_cast::bytes("wb", &[0, 1, 0, 7, 1, 0]));
+        let weight = model.meshes[0].vertices.vertex(1).weight(1);
+
+        assert_eq!((weight.bone, weight.value), (0, 0.0));
+    }
+
+    #[test]
+    fn reads_float_and_legacy_colors() {
+        let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+
+        let float_colors = test_cast::node(
+            "mesh",
+            0x40,
+            &[
+                test_cast::vectors("vp", &positions),
+                test_cast::bytes("cl", &[1]),
+                test_cast::vectors("c0", &[[1.0, 0.5, 0.0, 1.0]; 3]),
+            ],
+            &[],
+        );
+
+        // Older files store a single layer as vc, without a layer count.
+        let legacy_colors = test_cast::node(
+            "mesh",
+            0x41,
+            &[
+                test_cast::vectors("vp", &positions),
+                test_cast::integers("vc", &[0x8000_FF00; 3]),
+            ],
+            &[],
+        );
+
+        let model = read_model(&test_cast::model_file(
+            "colors",
+            &[float_colors, legacy_colors],
+        ));
+
+        let colors: Vec<[u8; 4]> = model
+            .meshes
+            .iter()
+            .map(|mesh| {
+                let color = mesh.vertices.vertex(2).color(0);
+
+                [color.r, color.g, color.b, color.a]
+            })
+            .collect();
+
+        assert_eq!(colors, [[0xFF, 0x80, 0, 0xFF], [0, 0xFF, 0, 0x80]]);
+    }
+
+    #[test]
+    fn reads_blend_shapes_as_deltas() {
+        let model = read_model(&test_cast::blend_shape_model());
+
+        // The target shape is only part of the blend shape, not a mesh of its own.
+        assert_eq!(model.meshes.len(), 1);
+        assert_eq!(model.meshes[0].faces.len(), 1);
+
+        let blend_shapes = &model.meshes[0].blend_shapes;
+
+        assert_eq!(blend_shapes.len(), 1);
+        assert_eq!(blend_shapes[0].name, "raise");
+        assert_eq!(blend_shapes[0].target_scale, 0.5);
+        assert_eq!(blend_shapes[0].vertex_deltas.len(), 1);
+        assert_near(blend_shapes[0].vertex_deltas[&2], [0.0, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn reconciles_local_and_world_bone_transforms() {
+        let rotation = [0.0, 0.0, FRAC_1_SQRT_2, FRAC_1_SQRT_2];
+
+        let bones = [
+            // Rotated a quarter turn around z and scaled, with local transforms only
+            test_cast::node(
+                "bone",
+                0x10,
+                &[
+                    test_cast::integers("p", &[u32::MAX]),
+                    test_cast::vectors("lp", &[[0.0, 1.0, 0.0]]),
+                    test_cast::vectors("lr", &[rotation]),
+                    test_cast::vectors("s", &[[2.0, 2.0, 2.0]]),
+                ],
+                &[],
+            ),
+            test_cast::node(
+                "bone",
+                0x11,
+                &[
+                    test_cast::integers("p", &[0]),
+                    test_cast::vectors("lp", &[[1.0, 0.0, 0.0]]),
+                ],
+                &[],
+            ),
+            // World transforms only
+            test_cast::node(
+                "bone",
+                0x12,
+                &[
+                    test_cast::integers("p", &[1]),
+                    test_cast::vectors("wp", &[[0.0, 3.0, 1.0]]),
+                    test_cast::vectors("wr", &[rotation]),
+                ],
+                &[],
+            ),
+        ];
+
+        let skeleton = test_cast::node("skel", 0x2, &[], &bones);
+        let model = read_model(&test_cast::model_file("bones", &[skeleton]));
+        let bones = &model.skeleton.bones;
+
+        assert_near(bones[0].world_position, [0.0, 1.0, 0.0]);
+        assert_near(bones[0].world_scale, [2.0, 2.0, 2.0]);
+
+        assert_near(bones[1].world_position, [0.0, 3.0, 0.0]);
+        assert_near(bones[1].world_scale, [2.0, 2.0, 2.0]);
+        assert!((bones[1].world_rotation.z - FRAC_1_SQRT_2).abs() < 1e-5);
+
+        assert_near(bones[2].local_position, [0.0, 0.0, 0.5]);
+        assert!((bones[2].local_rotation.w - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn compensates_only_the_direct_parent_scale() {
+        let bone = |hash: u64, parent: u32, scale: f32, compensate: bool| {
+            test_cast::node(
+                "bone",
+                hash,
+                &[
+                    test_cast::integers("p", &[parent]),
+                    test_cast::vectors("lp", &[[0.0, 1.0, 0.0]]),
+                    test_cast::vectors("s", &[[scale; 3]]),
+                    test_cast::bytes("ssc", &[compensate as u8]),
+                ],
+                &[],
+            )
+        };
+
+        let bones = [
+            bone(0x10, u32::MAX, 2.0, false),
+            bone(0x11, 0, 3.0, false),
+            bone(0x12, 1, 1.0, true),
+        ];
+
+        let skeleton = test_cast::node("skel", 0x2, &[], &bones);
+        let model = read_model(&test_cast::model_file("bones", &[skeleton]));
+        let bones = &model.skeleton.bones;
+
+        assert_near(bones[1].world_scale, [6.0, 6.0, 6.0]);
+
+        // The scale of the grandparent is still inherited, only the parent's own scale is compensated.
+        assert_near(bones[2].world_scale, [2.0, 2.0, 2.0]);
+        assert_near(bones[2].world_position, [0.0, 9.0, 0.0]);
+    }
 }
@@ -0,0 +1,1012 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, Cursor, Read, Seek};
+use std::path::Path;
+
+use porter_app::AssetTreeNode;
+use porter_app::AssetTreeProperty;
+
+use crate::asset_manager::AssetType;
+
+/// Magic at the start of every cast file, `cast` in little endian
+const CAST_MAGIC: u32 = 0x7473_6163;
+
+/// Node identifiers, the four character node type in little endian
+const NODE_MODEL: u32 = 0x6C64_6F6D;
+const NODE_MESH: u32 = 0x6873_656D;
+const NODE_BLEND_SHAPE: u32 = 0x6873_6C62;
+const NODE_SKELETON: u32 = 0x6C65_6B73;
+const NODE_BONE: u32 = 0x656E_6F62;
+const NODE_MATERIAL: u32 = 0x6C74_616D;
+const NODE_ANIMATION: u32 = 0x6D69_6E61;
+const NODE_CURVE: u32 = 0x7672_7563;
+const NODE_FILE: u32 = 0x656C_6966;
+const NODE_METADATA: u32 = 0x6174_656D;
+
+/// Property types, the one or two character property type in little endian
+const PROPERTY_BYTE: u16 = 0x0062;
+const PROPERTY_SHORT: u16 = 0x0068;
+const PROPERTY_INTEGER32: u16 = 0x0069;
+const PROPERTY_INTEGER64: u16 = 0x006C;
+const PROPERTY_FLOAT: u16 = 0x0066;
+const PROPERTY_DOUBLE: u16 = 0x0064;
+const PROPERTY_STRING: u16 = 0x0073;
+const PROPERTY_VECTOR2: u16 = 0x3276;
+const PROPERTY_VECTOR3: u16 = 0x3376;
+const PROPERTY_VECTOR4: u16 = 0x3476;
+
+/// Size of a node header: identifier, size, hash, property count and child count
+const NODE_HEADER_SIZE: u64 = 24;
+
+/// Metadata properties with a well known meaning, and the names they're shown by
+const METADATA_FIELDS: [(&str, &str); 3] = [("a", "Author"), ("s", "Software"), ("up", "Up Axis")];
+
+/// Number of values kept for each property in the structure tree
+const STRUCTURE_VALUES: usize = 8;
+
+/// Deepest nesting of nodes read into the structure tree, real files only nest a few levels
+const MAX_STRUCTURE_DEPTH: usize = 64;
+
+/// Counts gathered for an asset while scanning, without parsing any geometry
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AssetCounts {
+    pub meshes: usize,
+    pub bones: usize,
+    pub materials: usize,
+    pub vertices: usize,
+    pub faces: usize,
+    pub curves: usize,
+    pub textures: usize,
+    /// Frames spanned by the keyframes of every curve
+    pub frames: usize,
+    pub framerate: f32,
+}
+
+/// A model or animation node found while scanning a cast file
+#[derive(Debug)]
+pub struct ScannedAsset {
+    pub asset_type: AssetType,
+    pub root_index: usize,
+    pub node_index: usize,
+    /// Index of the material among its model's materials, zero for other assets
+    pub material_index: usize,
+    pub name: Option<String>,
+    pub counts: AssetCounts,
+    /// Fields of the metadata node in the asset's root, by display name
+    pub metadata: Vec<(String, String)>,
+}
+
+struct NodeHeader {
+    identifier: u32,
+    hash: u64,
+    property_count: u32,
+    child_count: u32,
+    /// Stream position right after the node and all of its children
+    end: u64,
+}
+
+/// A property whose payload was skipped, unless it was a string, a list of hashes or a single float
+struct ScannedProperty {
+    name: String,
+    array_length: u32,
+    string: Option<String>,
+    hashes: Vec<u64>,
+    float: Option<f32>,
+}
+
+/// Walks the node headers of the given cast file, skipping over property payloads and geometry.
+pub fn scan_cast_file(file_name: &Path) -> Result<Vec<ScannedAsset>, String> {
+    let file = File::open(file_name).map_err(|e| format!("Could not open: {e}"))?;
+
+    let mut reader = BufReader::new(file);
+
+    scan_cast(&mut reader).map_err(|e| format!("Error scanning file: {e}"))
+}
+
+fn scan_cast<R: Read + Seek>(reader: &mut R) -> std::io::Result<Vec<ScannedAsset>> {
+    if read_u32(reader)? != CAST_MAGIC {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "not a cast file",
+        ));
+    }
+
+    let _version = read_u32(reader)?;
+    let root_count = read_u32(reader)?;
+    let _flags = read_u32(reader)?;
+
+    let mut assets = Vec::new();
+
+    for root_index in 0..root_count as usize {
+        let root = read_node_header(reader)?;
+
+        skip_properties(reader, root.property_count)?;
+
+        let first_asset = assets.len();
+
+        let mut model_index = 0;
+        let mut animation_index = 0;
+        let mut metadata = Vec::new();
+
+        for _ in 0..root.child_count {
+            let child = read_node_header(reader)?;
+
+            match child.identifier {
+                NODE_MODEL => {
+                    assets.extend(scan_model(reader, &child, root_index, model_index)?);
+                    model_index += 1;
+                }
+                NODE_ANIMATION => {
+                    assets.push(scan_animation(reader, &child, root_index, animation_index)?);
+                    animation_index += 1;
+                }
+                NODE_METADATA => {
+                    metadata.extend(scan_metadata(reader, &child)?);
+                    skip_to(reader, child.end)?;
+                }
+                _ => skip_to(reader, child.end)?,
+            }
+        }
+
+        skip_to(reader, root.end)?;
+
+        // The metadata node may come after the assets it describes.
+        for asset in &mut assets[first_asset..] {
+            asset.metadata = metadata.clone();
+        }
+    }
+
+    Ok(assets)
+}
+
+/// Reads every node of the given cast file and the first few values of each property, for inspecting its structure.
+pub fn read_cast_structure(file_name: &Path) -> Result<Vec<AssetTreeNode>, String> {
+    let file = File::open(file_name).map_err(|e| format!("Could not open: {e}"))?;
+
+    let mut reader = BufReader::new(file);
+
+    read_structure(&mut reader).map_err(|e| format!("Error reading structure: {e}"))
+}
+
+fn read_structure<R: Read + Seek>(reader: &mut R) -> std::io::Result<Vec<AssetTreeNode>> {
+    if read_u32(reader)? != CAST_MAGIC {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "not a cast file",
+        ));
+    }
+
+    let _version = read_u32(reader)?;
+    let root_count = read_u32(reader)?;
+    let _flags = read_u32(reader)?;
+
+    (0..root_count)
+        .map(|_| read_structure_node(reader, 0))
+        .collect()
+}
+
+fn read_structure_node<R: Read + Seek>(
+    reader: &mut R,
+    depth: usize,
+) -> std::io::Result<AssetTreeNode> {
+    // Nesting comes from the file, and would otherwise recurse until the stack overflows.
+    if depth >= MAX_STRUCTURE_DEPTH {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("nodes are nested deeper than {MAX_STRUCTURE_DEPTH} levels"),
+        ));
+    }
+
+    let header = read_node_header(reader)?;
+
+    let mut node = AssetTreeNode::new(format!(
+        "{} {:#X} ({} children)",
+        node_identifier(header.identifier),
+        header.hash,
+        header.child_count
+    ));
+
+    for _ in 0..header.property_count {
+        node.properties.push(read_structure_property(reader)?);
+    }
+
+    for _ in 0..header.child_count {
+        node.children.push(read_structure_node(reader, depth + 1)?);
+    }
+
+    skip_to(reader, header.end)?;
+
+    Ok(node)
+}
+
+fn read_structure_property<R: Read + Seek>(reader: &mut R) -> std::io::Result<AssetTreeProperty> {
+    let property_type = read_u16(reader)?;
+    let name_size = read_u16(reader)? as usize;
+    let array_length = read_u32(reader)? as usize;
+
+    let mut name = vec![0; name_size];
+
+    reader.read_exact(&mut name)?;
+
+    let name = String::from_utf8_lossy(&name).into_owned();
+
+    if property_type == PROPERTY_STRING {
+        return Ok(AssetTreeProperty {
+            name,
+            kind: "string".to_string(),
+            count: 1,
+            values: vec![format!("{:?}", read_string(reader)?)],
+        });
+    }
+
+    let element_size = property_element_size(property_type)?;
+    let kept = array_length.min(STRUCTURE_VALUES);
+
+    let mut values = Vec::with_capacity(kept);
+
+    for _ in 0..kept {
+        values.push(read_structure_value(reader, property_type)?);
+    }
+
+    reader.seek_relative((array_length - kept) as i64 * element_size)?;
+
+    let kind = match property_type {
+        PROPERTY_BYTE => "byte",
+        PROPERTY_SHORT => "short",
+        PROPERTY_INTEGER32 => "int32",
+        PROPERTY_INTEGER64 => "int64",
+        PROPERTY_FLOAT => "float",
+        PROPERTY_DOUBLE => "double",
+        PROPERTY_VECTOR2 => "vector2",
+        PROPERTY_VECTOR3 => "vector3",
+        _ => "vector4",
+    };
+
+    Ok(AssetTreeProperty {
+        name,
+        kind: kind.to_string(),
+        count: array_length,
+        values,
+    })
+}
+
+/// Reads and formats a single value of the given property type.
+fn read_structure_value<R: Read>(reader: &mut R, property_type: u16) -> std::io::Result<String> {
+    let vector = |reader: &mut R, length: usize| -> std::io::Result<String> {
+        let components = (0..length)
+            .map(|_| read_f32(reader).map(|value| value.to_string()))
+            .collect::<std::io::Result<Vec<_>>>()?;
+
+        Ok(format!("({})", components.join(", ")))
+    };
+
+    Ok(match property_type {
+        PROPERTY_BYTE => {
+            let mut buffer = [0; 1];
+
+            reader.read_exact(&mut buffer)?;
+
+            buffer[0].to_string()
+        }
+        PROPERTY_SHORT => read_u16(reader)?.to_string(),
+        PROPERTY_INTEGER32 => read_u32(reader)?.to_string(),
+        // 64 bit integers are almost always hashes of other nodes.
+        PROPERTY_INTEGER64 => format!("{:#X}", read_u64(reader)?),
+        PROPERTY_FLOAT => read_f32(reader)?.to_string(),
+        PROPERTY_DOUBLE => f64::from_bits(read_u64(reader)?).to_string(),
+        PROPERTY_VECTOR2 => vector(reader, 2)?,
+        PROPERTY_VECTOR3 => vector(reader, 3)?,
+        _ => vector(reader, 4)?,
+    })
+}
+
+/// Formats a node identifier as its four character node type.
+fn node_identifier(identifier: u32) -> String {
+    let bytes = identifier.to_le_bytes();
+
+    if bytes
+        .iter()
+        .all(|byte| byte.is_ascii_graphic() || *byte == 0)
+    {
+        String::from_utf8_lossy(&bytes)
+            .trim_end_matches('\0')
+            .to_string()
+    } else {
+        format!("{identifier:#X}")
+    }
+}
+
+/// A node exactly as stored, with the raw bytes of every property, for comparing two files node by node
+#[derive(Debug, PartialEq)]
+pub struct RawNode {
+    pub identifier: u32,
+    pub hash: u64,
+    pub properties: Vec<RawProperty>,
+    pub children: Vec<RawNode>,
+}
+
+/// A property exactly as stored
+#[derive(Debug, PartialEq)]
+pub struct RawProperty {
+    pub property_type: u16,
+    pub name: String,
+    pub array_length: u32,
+    pub data: Vec<u8>,
+}
+
+/// Reads every node and property of the cast data in the given buffer, without interpreting any values.
+pub fn read_raw_nodes(buffer: &[u8]) -> Result<Vec<RawNode>, String> {
+    let mut reader = Cursor::new(buffer);
+
+    read_raw(&mut reader).map_err(|e| format!("Error reading nodes: {e}"))
+}
+
+fn read_raw<R: Read + Seek>(reader: &mut R) -> std::io::Result<Vec<RawNode>> {
+    if read_u32(reader)? != CAST_MAGIC {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "not a cast file",
+        ));
+    }
+
+    let _version = read_u32(reader)?;
+    let root_count = read_u32(reader)?;
+    let _flags = read_u32(reader)?;
+
+    (0..root_count).map(|_| read_raw_node(reader)).collect()
+}
+
+fn read_raw_node<R: Read + Seek>(reader: &mut R) -> std::io::Result<RawNode> {
+    let header = read_node_header(reader)?;
+
+    // The counts come straight from the file, so nothing is reserved up front.
+    let mut node = RawNode {
+        identifier: header.identifier,
+        hash: header.hash,
+        properties: Vec::new(),
+        children: Vec::new(),
+    };
+
+    for _ in 0..header.property_count {
+        let property_type = read_u16(reader)?;
+        let name_size = read_u16(reader)? as u64;
+        let array_length = read_u32(reader)?;
+
+        ensure_remaining(reader, header.end, name_size)?;
+
+        let mut name = vec![0; name_size as usize];
+
+        reader.read_exact(&mut name)?;
+
+        let data = if property_type == PROPERTY_STRING {
+            read_string(reader)?.into_bytes()
+        } else {
+            let size = (property_element_size(property_type)? as u64)
+                .checked_mul(array_length as u64)
+                .unwrap_or(u64::MAX);
+
+            ensure_remaining(reader, header.end, size)?;
+
+            let mut data = vec![0; size as usize];
+
+            reader.read_exact(&mut data)?;
+
+            data
+        };
+
+        node.properties.push(RawProperty {
+            property_type,
+            name: String::from_utf8_lossy(&name).into_owned(),
+            array_length,
+            data,
+        });
+    }
+
+    for _ in 0..header.child_count {
+        node.children.push(read_raw_node(reader)?);
+    }
+
+    skip_to(reader, header.end)?;
+
+    Ok(node)
+}
+
+/// Describes the first place the two node trees differ, if they do.
+pub fn raw_difference(expected: &[RawNode], actual: &[RawNode], path: &str) -> Option<String> {
+    if expected.len() != actual.len() {
+        return Some(format!(
+            "{path}: expected {} nodes, found {}",
+            expected.len(),
+            actual.len()
+        ));
+    }
+
+    for (index, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+        let path = format!("{path}/{}[{index}]", node_identifier(expected.identifier));
+
+        if expected.identifier != actual.identifier {
+            return Some(format!(
+                "{path}: found a {} node",
+                node_identifier(actual.identifier)
+            ));
+        }
+
+        if expected.hash != actual.hash {
+            return Some(format!(
+                "{path}: expected hash {:#X}, found {:#X}",
+                expected.hash, actual.hash
+            ));
+        }
+
+        if expected.properties.len() != actual.properties.len() {
+            return Some(format!(
+                "{path}: expected {} properties, found {}",
+                expected.properties.len(),
+                actual.properties.len()
+            ));
+        }
+
+        for (expected, actual) in expected.properties.iter().zip(&actual.properties) {
+            if expected != actual {
+                return Some(format!(
+                    "{path}: property {} differs, expected {} values of type {:#X}, found {} of type {:#X} named {}",
+                    expected.name,
+                    expected.array_length,
+                    expected.property_type,
+                    actual.array_length,
+                    actual.property_type,
+                    actual.name
+                ));
+            }
+        }
+
+        if let Some(difference) = raw_difference(&expected.children, &actual.children, &path) {
+            return Some(difference);
+        }
+    }
+
+    None
+}
+
+/// Scans a model node, returning the model followed by each of its materials.
+fn scan_model<R: Read + Seek>(
+    reader: &mut R,
+    model: &NodeHeader,
+    root_index: usize,
+    node_index: usize,
+) -> std::io::Result<Vec<ScannedAsset>> {
+    let properties = read_properties(reader, model.property_count)?;
+
+    let mut counts = AssetCounts::default();
+    let mut materials = Vec::new();
+    let mut meshes: Vec<(u64, usize, usize)> = Vec::new();
+    let mut targets: HashSet<u64> = HashSet::new();
+
+    for _ in 0..model.child_count {
+        let child = read_node_header(reader)?;
+
+        match child.identifier {
+            NODE_MESH => {
+                let properties = read_properties(reader, child.property_count)?;
+
+                let vertices = property_length(&properties, "vp");
+                let faces = property_length(&properties, "f") / 3;
+
+                meshes.push((child.hash, vertices, faces));
+            }
+            NODE_SKELETON => {
+                skip_properties(reader, child.property_count)?;
+
+                for _ in 0..child.child_count {
+                    let bone = read_node_header(reader)?;
+
+                    if bone.identifier == NODE_BONE {
+                        counts.bones += 1;
+                    }
+
+                    skip_to(reader, bone.end)?;
+                }
+            }
+            NODE_MATERIAL => {
+                let properties = read_properties(reader, child.property_count)?;
+
+                let mut textures = 0;
+
+                for _ in 0..child.child_count {
+                    let file = read_node_header(reader)?;
+
+                    if file.identifier == NODE_FILE {
+                        textures += 1;
+                    }
+
+                    skip_to(reader, file.end)?;
+                }
+
+                materials.push(ScannedAsset {
+                    asset_type: AssetType::Material,
+                    root_index,
+                    node_index,
+                    material_index: counts.materials,
+                    name: string_property(&properties, "n"),
+                    counts: AssetCounts {
+                        textures,
+                        ..Default::default()
+                    },
+                    metadata: Vec::new(),
+                });
+
+                counts.materials += 1;
+            }
+            NODE_BLEND_SHAPE => {
+                let properties = read_properties(reader, child.property_count)?;
+
+                if let Some(property) = properties.iter().find(|p| p.name == "t") {
+                    targets.extend(property.hashes.iter().copied());
+                }
+            }
+            _ => {}
+        }
+
+        skip_to(reader, child.end)?;
+    }
+
+    // Blend shape targets are stored as meshes, but aren't drawn on their own.
+    for (_, vertices, faces) in meshes
+        .into_iter()
+        .filter(|(hash, _, _)| !targets.contains(hash))
+    {
+        counts.meshes += 1;
+        counts.vertices += vertices;
+        counts.faces += faces;
+    }
+
+    skip_to(reader, model.end)?;
+
+    materials.insert(
+        0,
+        ScannedAsset {
+            asset_type: AssetType::Model,
+            root_index,
+            node_index,
+            material_index: 0,
+            name: string_property(&properties, "n"),
+            counts,
+            metadata: Vec::new(),
+        },
+    );
+
+    Ok(materials)
+}
+
+fn scan_animation<R: Read + Seek>(
+    reader: &mut R,
+    animation: &NodeHeader,
+    root_index: usize,
+    node_index: usize,
+) -> std::io::Result<ScannedAsset> {
+    let properties = read_properties(reader, animation.property_count)?;
+
+    let mut counts = AssetCounts::default();
+
+    for _ in 0..animation.child_count {
+        let child = read_node_header(reader)?;
+
+        if child.identifier == NODE_CURVE {
+            counts.curves += 1;
+            counts.frames = counts.frames.max(scan_curve_frames(reader, &child)?);
+        }
+
+        skip_to(reader, child.end)?;
+    }
+
+    skip_to(reader, animation.end)?;
+
+    counts.framerate = properties
+        .iter()
+        .find(|p| p.name == "fr")
+        .and_then(|p| p.float)
+        .unwrap_or_default();
+
+    Ok(ScannedAsset {
+        asset_type: AssetType::Animation,
+        root_index,
+        node_index,
+        material_index: 0,
+        name: string_property(&properties, "n"),
+        counts,
+        metadata: Vec::new(),
+    })
+}
+
+/// The number of frames spanned by the keyframes of a curve node, the last keyframe plus one.
+fn scan_curve_frames<R: Read + Seek>(reader: &mut R, curve: &NodeHeader) -> std::io::Result<usize> {
+    let mut frames = 0;
+
+    for _ in 0..curve.property_count {
+        let property_type = read_u16(reader)?;
+        let name_size = read_u16(reader)? as u64;
+        let array_length = read_u32(reader)?;
+
+        ensure_remaining(reader, curve.end, name_size)?;
+
+        let mut name = vec![0; name_size as usize];
+
+        reader.read_exact(&mut name)?;
+
+        if property_type == PROPERTY_STRING {
+            read_string(reader)?;
+            continue;
+        }
+
+        let read_frame: fn(&mut R) -> std::io::Result<u32> = match property_type {
+            PROPERTY_BYTE if name == b"kb" => |reader: &mut R| read_u8(reader).map(u32::from),
+            PROPERTY_SHORT if name == b"kb" => |reader: &mut R| read_u16(reader).map(u32::from),
+            PROPERTY_INTEGER32 if name == b"kb" => read_u32,
+            _ => {
+                let size = property_element_size(property_type)? * array_length as i64;
+
+                reader.seek_relative(size)?;
+                continue;
+            }
+        };
+
+        for _ in 0..array_length {
+            frames = frames.max(read_frame(reader)? as usize + 1);
+        }
+    }
+
+    Ok(frames)
+}
+
+/// Reads the string fields of a metadata node, naming the well known ones.
+fn scan_metadata<R: Read + Seek>(
+    reader: &mut R,
+    metadata: &NodeHeader,
+) -> std::io::Result<Vec<(String, String)>> {
+    let properties = read_properties(reader, metadata.property_count)?;
+
+    Ok(properties
+        .into_iter()
+        .filter_map(|property| {
+            let value = property.string.filter(|value| !value.is_empty())?;
+
+            let name = METADATA_FIELDS
+                .iter()
+                .find(|(field, _)| *field == property.name)
+                .map(|(_, name)| name.to_string())
+                .unwrap_or(property.name);
+
+            Some((name, value))
+        })
+        .collect())
+}
+
+fn read_node_header<R: Read + Seek>(reader: &mut R) -> std::io::Result<NodeHeader> {
+    let start = reader.stream_position()?;
+
+    let identifier = read_u32(reader)?;
+    let size = read_u32(reader)? as u64;
+    let hash = read_u64(reader)?;
+    let property_count = read_u32(reader)?;
+    let child_count = read_u32(reader)?;
+
+    if size < NODE_HEADER_SIZE {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("node at {start:#X} is smaller than its header"),
+        ));
+    }
+
+    Ok(NodeHeader {
+        identifier,
+        hash,
+        property_count,
+        child_count,
+        end: start + size,
+    })
+}
+
+fn read_properties<R: Read + Seek>(
+    reader: &mut R,
+    count: u32,
+) -> std::io::Result<Vec<ScannedProperty>> {
+    // The count comes from the file, so it isn't trusted to size the list up front.
+    let mut properties = Vec::new();
+
+    for _ in 0..count {
+        let property_type = read_u16(reader)?;
+        let name_size = read_u16(reader)? as usize;
+        let array_length = read_u32(reader)?;
+
+        let mut name = vec![0; name_size];
+
+        reader.read_exact(&mut name)?;
+
+        let name = String::from_utf8_lossy(&name).into_owned();
+
+        let mut property = ScannedProperty {
+            name,
+            array_length,
+            string: None,
+            hashes: Vec::new(),
+            float: None,
+        };
+
+        match property_type {
+            PROPERTY_STRING => property.string = Some(read_string(reader)?),
+            PROPERTY_FLOAT if array_length == 1 => property.float = Some(read_f32(reader)?),
+            PROPERTY_INTEGER64 => {
+                property.hashes = (0..array_length)
+                    .map(|_| read_u64(reader))
+                    .collect::<std::io::Result<_>>()?;
+            }
+            _ => {
+                let size = property_element_size(property_type)? * array_length as i64;
+
+                reader.seek_relative(size)?;
+            }
+        }
+
+        properties.push(property);
+    }
+
+    Ok(properties)
+}
+
+fn skip_properties<R: Read + Seek>(reader: &mut R, count: u32) -> std::io::Result<()> {
+    read_properties(reader, count).map(|_| ())
+}
+
+fn property_element_size(property_type: u16) -> std::io::Result<i64> {
+    Ok(match property_type {
+        PROPERTY_BYTE => 1,
+        PROPERTY_SHORT => 2,
+        PROPERTY_INTEGER32 | PROPERTY_FLOAT => 4,
+        PROPERTY_INTEGER64 | PROPERTY_DOUBLE | PROPERTY_VECTOR2 => 8,
+        PROPERTY_VECTOR3 => 12,
+        PROPERTY_VECTOR4 => 16,
+        _ => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("unknown property type {property_type:#X}"),
+            ));
+        }
+    })
+}
+
+fn string_property(properties: &[ScannedProperty], name: &str) -> Option<String> {
+    properties
+        .iter()
+        .find(|p| p.name == name)
+        .and_then(|p| p.string.clone())
+}
+
+fn property_length(properties: &[ScannedProperty], name: &str) -> usize {
+    properties
+        .iter()
+        .find(|p| p.name == name)
+        .map(|p| p.array_length as usize)
+        .unwrap_or_default()
+}
+
+/// Moves forward to the given stream position, without discarding buffered data when possible.
+fn skip_to<R: Read + Seek>(reader: &mut R, end: u64) -> std::io::Result<()> {
+    let position = reader.stream_position()?;
+
+    if end < position {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "node size is smaller than its contents",
+        ));
+    }
+
+    reader.seek_relative((end - position) as i64)
+}
+
+/// Fails unless the given number of bytes fits before the end of the current node.
+fn ensure_remaining<R: Seek>(reader: &mut R, end: u64, size: u64) -> std::io::Result<()> {
+    let position = reader.stream_position()?;
+
+    if end.saturating_sub(position) < size {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("property at {position:#X} is larger than its node"),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Reads a null terminated string.
+fn read_string<R: Read>(reader: &mut R) -> std::io::Result<String> {
+    let mut buffer = Vec::new();
+    let mut byte = [0; 1];
+
+    loop {
+        reader.read_exact(&mut byte)?;
+
+        if byte[0] == 0 {
+            break;
+        }
+
+        buffer.push(byte[0]);
+    }
+
+    Ok(String::from_utf8_lossy(&buffer).into_owned())
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> std::io::Result<u8> {
+    let mut buffer = [0; 1];
+
+    reader.read_exact(&mut buffer)?;
+
+    Ok(buffer[0])
+}
+
+fn read_u16<R: Read>(reader: &mut R) -> std::io::Result<u16> {
+    let mut buffer = [0; 2];
+
+    reader.read_exact(&mut buffer)?;
+
+    Ok(u16::from_le_bytes(buffer))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> std::io::Result<u32> {
+    let mut buffer = [0; 4];
+
+    reader.read_exact(&mut buffer)?;
+
+    Ok(u32::from_le_bytes(buffer))
+}
+
+fn read_f32<R: Read>(reader: &mut R) -> std::io::Result<f32> {
+    read_u32(reader).map(f32::from_bits)
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> std::io::Result<u64> {
+    let mut buffer = [0; 8];
+
+    reader.read_exact(&mut buffer)?;
+
+    Ok(u64::from_le_bytes(buffer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_cast;
+
+    #[test]
+    fn scans_model_with_mesh() {
+        let buffer = test_cast::triangle_model();
+
+        let assets = scan_cast(&mut Cursor::new(&buffer)).unwrap();
+
+        assert_eq!(assets.len(), 2);
+
+        let model = &assets[0];
+
+        assert_eq!(model.asset_type, AssetType::Model);
+        assert_eq!(model.name.as_deref(), Some("triangle"));
+        assert_eq!(model.counts.meshes, 1);
+        assert_eq!(model.counts.bones, 2);
+        assert_eq!(model.counts.vertices, 3);
+        assert_eq!(model.counts.faces, 1);
+        assert_eq!(model.counts.materials, 1);
+
+        let material = &assets[1];
+
+        assert_eq!(material.asset_type, AssetType::Material);
+        assert_eq!(material.name.as_deref(), Some("skin"));
+        assert_eq!(material.counts.textures, 1);
+    }
+
+    #[test]
+    fn scans_animation_frames() {
+        let frames: Vec<u8> = [0u16, 24]
+            .iter()
+            .flat_map(|frame| frame.to_le_bytes())
+            .collect();
+
+        let curves = [
+            test_cast::node(
+                "curv",
+                0x11,
+                &[
+                    test_cast::string("nn", "root"),
+                    test_cast::string("kp", "rq"),
+                    test_cast::bytes("kb", &[0, 10]),
+                ],
+                &[],
+            ),
+            test_cast::node(
+                "curv",
+                0x12,
+                &[
+                    test_cast::string("nn", "root"),
+                    test_cast::string("kp", "tx"),
+                    test_cast::property("h", "kb", 2, &frames),
+                    test_cast::floats("kv", &[0.0, 1.0]),
+                ],
+                &[],
+            ),
+        ];
+
+        let animation = test_cast::node(
+            "anim",
+            0x10,
+            &[
+                test_cast::string("n", "walk"),
+                test_cast::floats("fr", &[30.0]),
+            ],
+            &curves,
+        );
+
+        let buffer = test_cast::file(&[test_cast::node("root", 0, &[], &[animation])]);
+
+        let assets = scan_cast(&mut Cursor::new(&buffer)).unwrap();
+
+        assert_eq!(assets.len(), 1);
+        assert_eq!(assets[0].asset_type, AssetType::Animation);
+        assert_eq!(assets[0].counts.curves, 2);
+        assert_eq!(assets[0].counts.frames, 25);
+        assert_eq!(assets[0].counts.framerate, 30.0);
+    }
+
+    #[test]
+    fn reads_vector_properties() {
+        let buffer = test_cast::file(&[test_cast::node(
+            "root",
+            0,
+            &[
+                test_cast::vectors("a", &[[1.0, 2.0]]),
+                test_cast::vectors("b", &[[1.0, 2.0, 3.0]]),
+                test_cast::vectors("c", &[[1.0, 2.0, 3.0, 4.0]]),
+            ],
+            &[],
+        )]);
+
+        let roots = read_structure(&mut Cursor::new(&buffer)).unwrap();
+        let root = &roots[0];
+
+        let kinds: Vec<&str> = root.properties.iter().map(|p| p.kind.as_str()).collect();
+
+        assert_eq!(kinds, ["vector2", "vector3", "vector4"]);
+        assert_eq!(root.properties[1].values, ["(1, 2, 3)"]);
+    }
+
+    #[test]
+    fn rejects_properties_larger_than_their_node() {
+        // Claims four billion floats while only holding one.
+        let buffer = test_cast::file(&[test_cast::node(
+            "root",
+            0,
+            &[test_cast::property(
+                "f",
+                "a",
+                u32::MAX,
+                &1.0f32.to_le_bytes(),
+            )],
+            &[],
+        )]);
+
+        assert!(read_raw_nodes(&buffer).is_err());
+        assert!(read_raw_nodes(&test_cast::triangle_model()).is_ok());
+    }
+
+    #[test]
+    fn rejects_deeply_nested_structures() {
+        let nested = |depth: usize| {
+            (0..depth).fold(test_cast::node("root", 0, &[], &[]), |child, _| {
+                test_cast::node("root", 0, &[], &[child])
+            })
+        };
+
+        let buffer = test_cast::file(&[nested(MAX_STRUCTURE_DEPTH)]);
+
+        let error = read_structure(&mut Cursor::new(&buffer)).unwrap_err();
+
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert!(read_structure(&mut Cursor::new(&test_cast::file(&[nested(8)]))).is_ok());
+    }
+}
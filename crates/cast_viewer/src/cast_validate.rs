@@ -0,0 +1,835 @@
+use porter_app::AssetIssue;
+use porter_cast::{CastFile, CastId, CastNode, CastPropertyId};
+use porter_math::{Quaternion, Vector2, Vector3, Vector4};
+use porter_model::Model;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use crate::cast_model::{MATERIAL_SLOTS, integer_values};
+use crate::texture_resolver::TextureResolver;
+
+/// Checks every model and animation in the cast file for data that can't be loaded as is.
+pub fn validate_cast_file(file: &CastFile) -> Vec<AssetIssue> {
+    let mut issues = Vec::new();
+
+    for (root_index, root) in file.roots().iter().enumerate() {
+        for (index, model_node) in root.children_of_type(CastId::Model).enumerate() {
+            let path = format!("root[{root_index}]/model[{index}]");
+
+            issues.extend(validate_model_node(model_node, &path));
+        }
+
+        for (index, animation_node) in root.children_of_type(CastId::Animation).enumerate() {
+            let path = format!("root[{root_index}]/animation[{index}]");
+
+            issues.extend(validate_animation_node(animation_node, &path));
+        }
+    }
+
+    issues
+}
+
+/// Checks a model node, its skeleton, materials, meshes and blend shapes.
+pub fn validate_model_node(model_node: &CastNode, path: &str) -> Vec<AssetIssue> {
+    let mut issues = Vec::new();
+
+    let bone_count = model_node
+        .children_of_type(CastId::Skeleton)
+        .next()
+        .map(|skeleton_node| {
+            validate_skeleton_node(skeleton_node, &format!("{path}/skeleton[0]"), &mut issues)
+        })
+        .unwrap_or_default();
+
+    let material_hashes: HashSet<u64> = model_node
+        .children_of_type(CastId::Material)
+        .map(|node| node.hash())
+        .collect();
+
+    for (index, material_node) in model_node.children_of_type(CastId::Material).enumerate() {
+        validate_material_node(
+            material_node,
+            &format!("{path}/material[{index}]"),
+            &mut issues,
+        );
+    }
+
+    let mesh_hashes: HashSet<u64> = model_node
+        .children_of_type(CastId::Mesh)
+        .map(|node| node.hash())
+        .collect();
+
+    for (index, mesh_node) in model_node.children_of_type(CastId::Mesh).enumerate() {
+        validate_mesh_node(
+            mesh_node,
+            &format!("{path}/mesh[{index}]"),
+            bone_count,
+            &material_hashes,
+            &mut issues,
+        );
+    }
+
+    for (index, blend_shape_node) in model_node.children_of_type(CastId::BlendShape).enumerate() {
+        validate_blend_shape_node(
+            model_node,
+            blend_shape_node,
+            &format!("{path}/blendshape[{index}]"),
+            &mesh_hashes,
+            &mut issues,
+        );
+    }
+
+    for (index, hair_node) in model_node.children_of_type(CastId::Hair).enumerate() {
+        let path = format!("{path}/hair[{index}]");
+
+        // Every strand has one more particle than segments
+        let required: usize = hair_node
+            .property("se")
+            .map(integer_values)
+            .unwrap_or_default()
+            .iter()
+            .map(|segments| *segments as usize + 1)
+            .sum();
+        let particles = hair_node
+            .property("pt")
+            .map(|p| p.values::<Vector3>().count())
+            .unwrap_or_default();
+
+        if particles < required {
+            issues.push(AssetIssue::error(
+                &path,
+                Some("pt"),
+                format!("{particles} particles for strands needing {required}"),
+            ));
+        } else if particles > required {
+            issues.push(AssetIssue::warning(
+                &path,
+                Some("pt"),
+                format!(
+                    "{} particles aren't part of any strand",
+                    particles - required
+                ),
+            ));
+        }
+
+        if let Some(hash) = hair_node
+            .property("m")
+            .and_then(|p| p.values::<u64>().next())
+            .filter(|hash| !material_hashes.contains(hash))
+        {
+            issues.push(AssetIssue::warning(
+                path,
+                Some("m"),
+                format!("references material {hash:#X} which isn't in the model"),
+            ));
+        }
+    }
+
+    issues
+}
+
+/// Checks that every texture of the model's materials can be found and previewed.
+pub fn validate_textures(model: &Model, resolver: &TextureResolver, path: &str) -> Vec<AssetIssue> {
+    let mut issues = Vec::new();
+
+    for (index, material) in model.materials.iter().enumerate() {
+        for texture in &material.textures {
+            if let Err(e) = resolver.resolve(&texture.file_name) {
+                issues.push(AssetIssue::warning(
+                    format!("{path}/material[{index}]"),
+                    Some(texture.texture_alias.as_str()),
+                    format!("{}: {e}", material.name),
+                ));
+            }
+        }
+    }
+
+    issues
+}
+
+/// Checks an animation node and its curves.
+pub fn validate_animation_node(animation_node: &CastNode, path: &str) -> Vec<AssetIssue> {
+    let mut issues = Vec::new();
+
+    if animation_node
+        .property("fr")
+        .and_then(|p| p.values::<f32>().next())
+        .is_some_and(|framerate| framerate <= 0.0 || !framerate.is_finite())
+    {
+        issues.push(AssetIssue::error(
+            path,
+            Some("fr"),
+            "framerate must be a positive number",
+        ));
+    }
+
+    for (index, curve_node) in animation_node.children_of_type(CastId::Curve).enumerate() {
+        let path = format!("{path}/curve[{index}]");
+
+        if curve_node
+            .property("nn")
+            .and_then(|p| p.values::<String>().next())
+            .is_none()
+        {
+            issues.push(AssetIssue::error(
+                &path,
+                Some("nn"),
+                "curve has no node name, it will be skipped",
+            ));
+        }
+
+        let Some(key_property) = curve_node
+            .property("kp")
+            .and_then(|p| p.values::<String>().next())
+        else {
+            issues.push(AssetIssue::error(
+                &path,
+                Some("kp"),
+                "curve has no key property, it will be skipped",
+            ));
+            continue;
+        };
+
+        let Some(key_values) = curve_node.property("kv") else {
+            issues.push(AssetIssue::error(
+                &path,
+                Some("kv"),
+                "curve has no key values, it will be skipped",
+            ));
+            continue;
+        };
+
+        let value_count = match key_property.as_str() {
+            "rq" => key_values.values::<Quaternion>().count(),
+            "vb" => integer_values(key_values).len(),
+            "tx" | "ty" | "tz" | "sx" | "sy" | "sz" => key_values.values::<f32>().count(),
+            _ => {
+                issues.push(AssetIssue::warning(
+                    &path,
+                    Some("kp"),
+                    format!("unsupported key property {key_property}, the curve will be skipped"),
+                ));
+                continue;
+            }
+        };
+
+        let frame_count = curve_node
+            .property("kb")
+            .map(|p| integer_values(p).len())
+            .unwrap_or_default();
+
+        if frame_count != value_count {
+            issues.push(AssetIssue::error(
+                &path,
+                Some("kb"),
+                format!("{frame_count} key frames but {value_count} key values"),
+            ));
+        }
+    }
+
+    issues
+}
+
+/// Checks the bones of a skeleton, returning the number of bones.
+fn validate_skeleton_node(
+    skeleton_node: &CastNode,
+    path: &str,
+    issues: &mut Vec<AssetIssue>,
+) -> usize {
+    let parents: Vec<Option<u32>> = skeleton_node
+        .children_of_type(CastId::Bone)
+        .map(|bone_node| {
+            bone_node
+                .property("p")
+                .and_then(|p| p.values::<u32>().next())
+                // A parent of -1 marks a root bone
+                .filter(|parent| *parent as i32 >= 0)
+        })
+        .collect();
+
+    let bone_count = parents.len();
+
+    for (index, parent) in parents.iter().enumerate() {
+        let Some(parent) = *parent else {
+            continue;
+        };
+
+        let bone_path = format!("{path}/bone[{index}]");
+
+        if parent as usize >= bone_count {
+            issues.push(AssetIssue::error(
+                bone_path,
+                Some("p"),
+                format!("parent index {parent} is outside the skeleton ({bone_count} bones)"),
+            ));
+        } else if parent as usize == index {
+            issues.push(AssetIssue::error(
+                bone_path,
+                Some("p"),
+                "bone is its own parent",
+            ));
+        }
+    }
+
+    // Following the parents from a bone in a cycle leads back to that bone
+    for (index, parent) in parents.iter().enumerate() {
+        let mut current = *parent;
+        let mut length = 1;
+
+        let in_cycle = loop {
+            match current.map(|parent| parent as usize) {
+                // Bones that are their own parent were reported above
+                Some(parent) if parent == index => break length > 1,
+                Some(parent) if parent < bone_count && length <= bone_count => {
+                    current = parents[parent];
+                    length += 1;
+                }
+                _ => break false,
+            }
+        };
+
+        if in_cycle {
+            issues.push(AssetIssue::error(
+                format!("{path}/bone[{index}]"),
+                Some("p"),
+                format!("parent chain forms a cycle of {length} bones"),
+            ));
+        }
+    }
+
+    let bone_hashes: HashSet<u64> = skeleton_node
+        .children_of_type(CastId::Bone)
+        .map(|node| node.hash())
+        .collect();
+
+    // The first bones of each node are required, the node is skipped without them.
+    let references = [
+        (
+            CastId::IKHandle,
+            "ik_handle",
+            2,
+            &["sb", "eb", "tb", "pvb", "pb"][..],
+        ),
+        (CastId::Constraint, "constraint", 2, &["cb", "tb"][..]),
+    ];
+
+    for (id, name, required, properties) in references {
+        for (index, node) in skeleton_node.children_of_type(id).enumerate() {
+            for (slot, property) in properties.iter().enumerate() {
+                let outcome = if slot < required {
+                    "so it's skipped"
+                } else {
+                    "so the reference is dropped"
+                };
+
+                let message = match node
+                    .property(property)
+                    .and_then(|p| p.values::<u64>().next())
+                {
+                    Some(hash) if !bone_hashes.contains(&hash) => {
+                        format!("references bone {hash:#X} which isn't in the skeleton, {outcome}")
+                    }
+                    None if slot < required => format!("is missing a bone, {outcome}"),
+                    _ => continue,
+                };
+
+                issues.push(AssetIssue::warning(
+                    format!("{path}/{name}[{index}]"),
+                    Some(*property),
+                    message,
+                ));
+            }
+        }
+    }
+
+    bone_count
+}
+
+fn validate_material_node(material_node: &CastNode, path: &str, issues: &mut Vec<AssetIssue>) {
+    let extra_slots = (0..)
+        .map(|index| format!("extra{index}"))
+        .take_while(|slot| material_node.property(slot).is_some());
+
+    let slots = MATERIAL_SLOTS
+        .iter()
+        .map(|(slot, _)| slot.to_string())
+        .chain(extra_slots);
+
+    for slot in slots {
+        let Some(texture_hash) = material_node
+            .property(&slot)
+            .and_then(|p| p.values::<u64>().next())
+        else {
+            continue;
+        };
+
+        match material_node.child_by_hash(texture_hash) {
+            Some(file_node) => {
+                if file_node
+                    .property("p")
+                    .and_then(|p| p.values::<String>().next())
+                    .is_none_or(|file_name| file_name.is_empty())
+                {
+                    issues.push(AssetIssue::warning(
+                        path,
+                        Some(&slot),
+                        format!("file node {texture_hash:#X} has no path"),
+                    ));
+                }
+            }
+            None => issues.push(AssetIssue::warning(
+                path,
+                Some(&slot),
+                format!("references missing file node {texture_hash:#X}"),
+            )),
+        }
+    }
+}
+
+fn validate_mesh_node(
+    mesh_node: &CastNode,
+    path: &str,
+    bone_count: usize,
+    material_hashes: &HashSet<u64>,
+    issues: &mut Vec<AssetIssue>,
+) {
+    let vertex_count = match mesh_node.property("vp") {
+        Some(positions) => positions.values::<Vector3>().count(),
+        None => {
+            issues.push(AssetIssue::error(path, Some("vp"), "mesh has no vertices"));
+            0
+        }
+    };
+
+    // Missing values are left at their defaults, extra ones are never read.
+    let layer_issue = |property: &str, count: usize, kind: &str| {
+        let message = match count.cmp(&vertex_count) {
+            Ordering::Less => format!(
+                "{kind} missing for {} of {vertex_count} vertices",
+                vertex_count - count
+            ),
+            Ordering::Greater => format!(
+                "{} extra {kind} ignored, the mesh has {vertex_count} vertices",
+                count - vertex_count
+            ),
+            Ordering::Equal => return None,
+        };
+
+        Some(AssetIssue::error(path, Some(property), message))
+    };
+
+    if let Some(normals) = mesh_node.property("vn") {
+        issues.extend(layer_issue(
+            "vn",
+            normals.values::<Vector3>().count(),
+            "normals",
+        ));
+    }
+
+    let uv_layers = mesh_node
+        .property("ul")
+        .and_then(|p| integer_values(p).first().copied())
+        .unwrap_or(0);
+
+    for layer in 0..uv_layers {
+        let property = format!("u{layer}");
+
+        match mesh_node.property(&property) {
+            Some(uvs) => issues.extend(layer_issue(
+                &property,
+                uvs.values::<Vector2>().count(),
+                "uvs",
+            )),
+            None => issues.push(AssetIssue::warning(
+                path,
+                Some(&property),
+                format!("uv layer {layer} is declared by ul but missing"),
+            )),
+        }
+    }
+
+    let color_layers = mesh_node
+        .property("cl")
+        .and_then(|p| integer_values(p).first().copied())
+        .unwrap_or(0);
+
+    for layer in 0..color_layers {
+        let property = format!("c{layer}");
+
+        match mesh_node.property(&property) {
+            Some(colors) => {
+                let count = match colors.id() {
+                    CastPropertyId::Vector4 => colors.values::<Vector4>().count(),
+                    _ => colors.values::<u32>().count(),
+                };
+
+                issues.extend(layer_issue(&property, count, "colors"));
+            }
+            None => issues.push(AssetIssue::warning(
+                path,
+                Some(&property),
+                format!("color layer {layer} is declared by cl but missing"),
+            )),
+        }
+    }
+
+    let maximum_influence = mesh_node
+        .property("mi")
+        .and_then(|p| integer_values(p).first().copied())
+        .unwrap_or(0) as usize;
+
+    if maximum_influence > 0 {
+        let expected = vertex_count * maximum_influence;
+
+        let weight_bones = mesh_node
+            .property("wb")
+            .map(integer_values)
+            .unwrap_or_default();
+        let weight_values: Vec<f32> = mesh_node
+            .property("wv")
+            .map(|p| p.values::<f32>().collect())
+            .unwrap_or_default();
+
+        if weight_bones.len() < expected {
+            issues.push(AssetIssue::error(
+                path,
+                Some("wb"),
+                format!(
+                    "{} weight bones for {vertex_count} vertices with {maximum_influence} influences",
+                    weight_bones.len()
+                ),
+            ));
+        }
+
+        if weight_values.len() < expected {
+            issues.push(AssetIssue::error(
+                path,
+                Some("wv"),
+                format!(
+                    "{} weight values for {vertex_count} vertices with {maximum_influence} influences",
+                    weight_values.len()
+                ),
+            ));
+        }
+
+        let invalid_bones = weight_bones
+            .iter()
+            .zip(weight_values.iter())
+            .filter(|(bone, value)| **bone as usize >= bone_count && **value > 0.0)
+            .count();
+
+        if invalid_bones > 0 {
+            issues.push(AssetIssue::warning(
+                path,
+                Some("wb"),
+                format!(
+                    "{invalid_bones} weights reference bones outside the skeleton ({bone_count} bones)"
+                ),
+            ));
+        }
+    }
+
+    if let Some(faces) = mesh_node.property("f") {
+        let indices = integer_values(faces);
+
+        if indices.len() % 3 != 0 {
+            issues.push(AssetIssue::warning(
+                path,
+                Some("f"),
+                format!(
+                    "{} face indices is not a multiple of 3, the remainder is ignored",
+                    indices.len()
+                ),
+            ));
+        }
+
+        let out_of_range: Vec<u32> = indices
+            .iter()
+            .copied()
+            .filter(|index| *index as usize >= vertex_count)
+            .collect();
+
+        if let Some(largest) = out_of_range.iter().max() {
+            issues.push(AssetIssue::error(
+                path,
+                Some("f"),
+                format!(
+                    "{} face indices are outside the {vertex_count} vertices (largest is {largest})",
+                    out_of_range.len()
+                ),
+            ));
+        }
+    }
+
+    if let Some(material_hash) = mesh_node
+        .property("m")
+        .and_then(|p| p.values::<u64>().next())
+        && !material_hashes.contains(&material_hash)
+    {
+        issues.push(AssetIssue::error(
+            path,
+            Some("m"),
+            format!("references missing material {material_hash:#X}"),
+        ));
+    }
+}
+
+fn validate_blend_shape_node(
+    model_node: &CastNode,
+    blend_shape_node: &CastNode,
+    path: &str,
+    mesh_hashes: &HashSet<u64>,
+    issues: &mut Vec<AssetIssue>,
+) {
+    let vertex_count = |node: &CastNode| {
+        node.property("vp")
+            .map(|p| p.values::<Vector3>().count())
+            .unwrap_or_default()
+    };
+
+    let base = match blend_shape_node
+        .property("b")
+        .and_then(|p| p.values::<u64>().next())
+    {
+        Some(hash) if mesh_hashes.contains(&hash) => model_node.child_by_hash(hash),
+        Some(hash) => {
+            issues.push(AssetIssue::error(
+                path,
+                Some("b"),
+                format!("base shape {hash:#X} is not a mesh of this model"),
+            ));
+            None
+        }
+        None => {
+            issues.push(AssetIssue::error(
+                path,
+                Some("b"),
+                "blend shape has no base shape",
+            ));
+            None
+        }
+    };
+
+    let target_hashes: Vec<u64> = blend_shape_node
+        .property("t")
+        .map(|p| p.values::<u64>().collect())
+        .unwrap_or_default();
+
+    if target_hashes.is_empty() {
+        issues.push(AssetIssue::error(
+            path,
+            Some("t"),
+            "blend shape has no target shapes",
+        ));
+    }
+
+    if let Some(target_scales) = blend_shape_node.property("ts") {
+        let count = target_scales.values::<f32>().count();
+
+        if count != target_hashes.len() {
+            issues.push(AssetIssue::warning(
+                path,
+                Some("ts"),
+                format!("{count} target scales for {} targets", target_hashes.len()),
+            ));
+        }
+    }
+
+    for target_hash in target_hashes {
+        let target = mesh_hashes
+            .contains(&target_hash)
+            .then(|| model_node.child_by_hash(target_hash))
+            .flatten();
+
+        let Some(target) = target else {
+            issues.push(AssetIssue::error(
+                path,
+                Some("t"),
+                format!("target shape {target_hash:#X} is not a mesh of this model"),
+            ));
+            continue;
+        };
+
+        if let Some(base) = base
+            && vertex_count(target) != vertex_count(base)
+        {
+            issues.push(AssetIssue::warning(
+                path,
+                Some("t"),
+                format!(
+                    "target shape {target_hash:#X} has {} vertices but the base shape has {}",
+                    vertex_count(target),
+                    vertex_count(base)
+                ),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_cast;
+    use std::io::Cursor;
+
+    /// The location of every issue found in the file, and whether it's an error.
+    fn issues(buffer: &[u8]) -> Vec<(String, bool)> {
+        let file = CastFile::read(&mut Cursor::new(buffer)).unwrap();
+
+        validate_cast_file(&file)
+            .iter()
+            .map(|issue| (issue.location(), issue.is_error()))
+            .collect()
+    }
+
+    fn expected(issues: &[(&str, bool)]) -> Vec<(String, bool)> {
+        issues
+            .iter()
+            .map(|(location, error)| (location.to_string(), *error))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_valid_models() {
+        assert!(issues(&test_cast::triangle_model()).is_empty());
+        assert!(issues(&test_cast::blend_shape_model()).is_empty());
+    }
+
+    #[test]
+    fn reports_broken_meshes() {
+        let mesh = test_cast::node(
+            "mesh",
+            0x40,
+            &[
+                test_cast::vectors("vp", &[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]),
+                test_cast::vectors("vn", &[[0.0, 0.0, 1.0], [0.0, 0.0, 1.0]]),
+                test_cast::bytes("ul", &[1]),
+                test_cast::bytes("mi", &[1]),
+                test_cast::integers("wb", &[0, 5, 1]),
+                test_cast::floats("wv", &[1.0, 1.0, 1.0]),
+                test_cast::integers("f", &[0, 1, 3]),
+                test_cast::hashes("m", &[0x99]),
+            ],
+            &[],
+        );
+
+        let buffer = test_cast::model_file("broken", &[test_cast::two_bone_skeleton(), mesh]);
+
+        assert_eq!(
+            issues(&buffer),
+            expected(&[
+                ("root[0]/model[0]/mesh[0].vn", true),
+                ("root[0]/model[0]/mesh[0].u0", false),
+                ("root[0]/model[0]/mesh[0].wb", false),
+                ("root[0]/model[0]/mesh[0].f", true),
+                ("root[0]/model[0]/mesh[0].m", true),
+            ])
+        );
+    }
+
+    #[test]
+    fn reports_missing_and_extra_vertex_values() {
+        let mesh = test_cast::node(
+            "mesh",
+            0x40,
+            &[
+                test_cast::vectors("vp", &[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]),
+                test_cast::vectors("vn", &[[0.0, 0.0, 1.0]]),
+                test_cast::bytes("ul", &[1]),
+                test_cast::vectors("u0", &[[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]]),
+                test_cast::integers("f", &[0, 1, 2]),
+            ],
+            &[],
+        );
+
+        let buffer = test_cast::model_file("layers", &[mesh]);
+        let file = CastFile::read(&mut Cursor::new(&buffer)).unwrap();
+
+        let messages: Vec<(String, String)> = validate_cast_file(&file)
+            .into_iter()
+            .map(|issue| (issue.location(), issue.message))
+            .collect();
+
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    "root[0]/model[0]/mesh[0].vn".to_string(),
+                    "normals missing for 2 of 3 vertices".to_string()
+                ),
+                (
+                    "root[0]/model[0]/mesh[0].u0".to_string(),
+                    "1 extra uvs ignored, the mesh has 3 vertices".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_broken_skeletons() {
+        let bone = |hash: u64, parent: u32| {
+            test_cast::node("bone", hash, &[test_cast::integers("p", &[parent])], &[])
+        };
+
+        let ik_handle = test_cast::node("ikhd", 0x20, &[test_cast::hashes("sb", &[0x99])], &[]);
+
+        let skeleton = test_cast::node(
+            "skel",
+            0x2,
+            &[],
+            &[bone(0x10, 1), bone(0x11, 0), bone(0x12, 2), ik_handle],
+        );
+
+        let buffer = test_cast::model_file("broken", &[skeleton]);
+
+        assert_eq!(
+            issues(&buffer),
+            expected(&[
+                ("root[0]/model[0]/skeleton[0]/bone[2].p", true),
+                ("root[0]/model[0]/skeleton[0]/bone[0].p", true),
+                ("root[0]/model[0]/skeleton[0]/bone[1].p", true),
+                ("root[0]/model[0]/skeleton[0]/ik_handle[0].sb", false),
+                ("root[0]/model[0]/skeleton[0]/ik_handle[0].eb", false),
+            ])
+        );
+    }
+
+    #[test]
+    fn reports_broken_curves() {
+        let curves = [
+            test_cast::node(
+                "curv",
+                0x11,
+                &[
+                    test_cast::string("kp", "tx"),
+                    test_cast::bytes("kb", &[0, 1]),
+                    test_cast::floats("kv", &[0.0]),
+                ],
+                &[],
+            ),
+            test_cast::node(
+                "curv",
+                0x12,
+                &[
+                    test_cast::string("nn", "root"),
+                    test_cast::string("kp", "xx"),
+                    test_cast::floats("kv", &[0.0]),
+                ],
+                &[],
+            ),
+        ];
+
+        let animation = test_cast::node("anim", 0x10, &[test_cast::floats("fr", &[0.0])], &curves);
+        let buffer = test_cast::file(&[test_cast::node("root", 0, &[], &[animation])]);
+
+        assert_eq!(
+            issues(&buffer),
+            expected(&[
+                ("root[0]/animation[0].fr", true),
+                ("root[0]/animation[0]/curve[0].nn", true),
+                ("root[0]/animation[0]/curve[0].kb", true),
+                ("root[0]/animation[0]/curve[1].kp", false),
+            ])
+        );
+    }
+}
@@ -0,0 +1,1338 @@
+use porter_cast::{CastFile, CastId, CastNode, CastProperty, CastPropertyId};
+use porter_math::{Quaternion, Vector2, Vector3, Vector4};
+use porter_model::{ConstraintType, MaterialTextureRef, Model};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::cast_model::{self, MATERIAL_SLOTS, ModelSources};
+
+/// Writes a parsed cast file back to the given path, keeping every node, hash and property as it was read.
+pub fn write_cast_file(file: &CastFile, file_name: &Path) -> Result<(), String> {
+    let output = File::create(file_name).map_err(|e| format!("Could not create: {e}"))?;
+
+    let mut writer = BufWriter::new(output);
+
+    write_cast(file, &mut writer)?;
+
+    writer
+        .flush()
+        .map_err(|e| format!("Error writing file: {e}"))
+}
+
+/// Serializes a parsed cast file to the given writer.
+pub fn write_cast<W: Write>(file: &CastFile, writer: &mut W) -> Result<(), String> {
+    file.write(writer)
+        .map_err(|e| format!("Error writing file: {e:?}"))
+}
+
+/// Copies the given cast file, writing each model node from the model returned for its root and model index.
+///
+/// Models without a replacement, and every other node, are copied as they are. Rewritten models keep the hashes,
+/// property order and property types of the nodes they were read from, along with anything the model doesn't hold,
+/// so a model that wasn't changed is written exactly as it was read.
+pub fn rewrite_models<F>(file: &CastFile, mut model: F) -> CastFile
+where
+    F: FnMut(usize, usize, &CastNode) -> Option<Model>,
+{
+    let mut output = CastFile::new();
+
+    for (root_index, root) in file.roots().iter().enumerate() {
+        let mut new_root = CastNode::new(root.identifier(), root.hash());
+
+        for property in root.properties() {
+            copy_property(&mut new_root, property);
+        }
+
+        let mut model_index = 0;
+
+        for child in root.children() {
+            if !matches!(child.identifier(), CastId::Model) {
+                copy_node(&mut new_root, child);
+                continue;
+            }
+
+            match model(root_index, model_index, child) {
+                Some(model) => ModelWriter::new(&model, Some(child)).write(&mut new_root),
+                None => copy_node(&mut new_root, child),
+            }
+
+            model_index += 1;
+        }
+
+        output.push(new_root);
+    }
+
+    output
+}
+
+/// Writes a model to a cast file of its own.
+///
+/// Given the model node it was read from, the model keeps everything `rewrite_models` keeps,
+/// otherwise it's written from scratch.
+pub fn model_file(model: &Model, source: Option<&CastNode>) -> CastFile {
+    let mut root = CastNode::new(CastId::Root, node_hash("root"));
+
+    ModelWriter::new(model, source).write(&mut root);
+
+    let mut output = CastFile::new();
+
+    output.push(root);
+    output
+}
+
+/// Writes a model over the model node it was read from, or from scratch without one.
+struct ModelWriter<'a> {
+    model: &'a Model,
+    source: Option<&'a CastNode>,
+    hash: u64,
+    sources: ModelSources<'a>,
+    bone_hashes: Vec<u64>,
+    material_hashes: Vec<u64>,
+    mesh_hashes: Vec<u64>,
+    /// The hash of the target mesh of every blend shape of each mesh
+    target_hashes: Vec<Vec<u64>>,
+}
+
+impl<'a> ModelWriter<'a> {
+    fn new(model: &'a Model, source: Option<&'a CastNode>) -> Self {
+        let sources = source.map(cast_model::model_sources).unwrap_or_default();
+        let hash = source.map_or_else(|| node_hash("model"), |source| source.hash());
+
+        // Parts read from a node keep its hash, new parts get one derived from their place in the model.
+        let hashes = |nodes: &[&CastNode], count: usize, kind: &str| -> Vec<u64> {
+            (0..count)
+                .map(|index| match nodes.get(index) {
+                    Some(node) => node.hash(),
+                    None => node_hash(&format!("{hash:#X}/{kind}[{index}]")),
+                })
+                .collect()
+        };
+
+        let target_hashes = model
+            .meshes
+            .iter()
+            .enumerate()
+            .map(|(mesh_index, mesh)| {
+                let targets: Vec<&CastNode> = sources
+                    .blend_shapes
+                    .get(mesh_index)
+                    .map(|targets| targets.iter().map(|(_, target)| *target).collect())
+                    .unwrap_or_default();
+
+                hashes(
+                    &targets,
+                    mesh.blend_shapes.len(),
+                    &format!("mesh[{mesh_index}]/blend_shape"),
+                )
+            })
+            .collect();
+
+        Self {
+            model,
+            source,
+            hash,
+            bone_hashes: hashes(&sources.bones, model.skeleton.bones.len(), "bone"),
+            material_hashes: hashes(&sources.materials, model.materials.len(), "material"),
+            mesh_hashes: hashes(&sources.meshes, model.meshes.len(), "mesh"),
+            target_hashes,
+            sources,
+        }
+    }
+
+    fn write(&self, parent: &mut CastNode) {
+        let model = self.model;
+        let sources = &self.sources;
+
+        let model_node = parent.create_with_hash(CastId::Model, self.hash);
+
+        for property in self.source.iter().flat_map(|source| source.properties()) {
+            copy_property(model_node, property);
+        }
+
+        for child in self.source.iter().flat_map(|source| source.children()) {
+            match child.identifier() {
+                CastId::Skeleton
+                    if sources
+                        .skeleton
+                        .is_some_and(|node| std::ptr::eq(node, child)) =>
+                {
+                    self.write_skeleton(model_node, Some(child));
+                }
+                CastId::Material => match index_of(&sources.materials, child) {
+                    Some(index) if index < model.materials.len() => {
+                        self.write_material(model_node, index, Some(child));
+                    }
+                    Some(_) => {}
+                    None => copy_node(model_node, child),
+                },
+                CastId::Mesh => match index_of(&sources.meshes, child) {
+                    Some(index) if index < model.meshes.len() => {
+                        self.write_mesh(model_node, index, Some(child));
+                    }
+                    Some(_) => {}
+                    None => match self.target_of(child) {
+                        Some((mesh_index, index)) if self.has_blend_shape(mesh_index, index) => {
+                            self.write_target(model_node, mesh_index, index, Some(child));
+                        }
+                        Some(_) => {}
+                        None => copy_node(model_node, child),
+                    },
+                },
+                CastId::BlendShape => match self.blend_shape_of(child) {
+                    Some((mesh_index, indices)) => {
+                        self.write_blend_shape(model_node, mesh_index, &indices, Some(child));
+                    }
+                    None => copy_node(model_node, child),
+                },
+                CastId::Hair => match index_of(&sources.hairs, child) {
+                    Some(index) if index < model.hairs.len() => {
+                        self.write_hair(model_node, index, Some(child));
+                    }
+                    Some(_) => {}
+                    None => copy_node(model_node, child),
+                },
+                _ => copy_node(model_node, child),
+            }
+        }
+
+        // Anything the source didn't have is added after it.
+        let skeleton = &model.skeleton;
+
+        if sources.skeleton.is_none()
+            && !(skeleton.bones.is_empty()
+                && skeleton.ik_handles.is_empty()
+                && skeleton.constraints.is_empty())
+        {
+            self.write_skeleton(model_node, None);
+        }
+
+        for index in sources.materials.len()..model.materials.len() {
+            self.write_material(model_node, index, None);
+        }
+
+        for index in sources.meshes.len()..model.meshes.len() {
+            self.write_mesh(model_node, index, None);
+        }
+
+        for (mesh_index, mesh) in model.meshes.iter().enumerate() {
+            let first = sources
+                .blend_shapes
+                .get(mesh_index)
+                .map(|targets| targets.len())
+                .unwrap_or_default();
+
+            let indices: Vec<usize> = (first..mesh.blend_shapes.len()).collect();
+
+            if indices.is_empty() {
+                continue;
+            }
+
+            for index in &indices {
+                self.write_target(model_node, mesh_index, *index, None);
+            }
+
+            self.write_blend_shape(model_node, mesh_index, &indices, None);
+        }
+
+        for index in sources.hairs.len()..model.hairs.len() {
+            self.write_hair(model_node, index, None);
+        }
+    }
+
+    fn write_skeleton(&self, model_node: &mut CastNode, source: Option<&CastNode>) {
+        let skeleton = &self.model.skeleton;
+        let sources = &self.sources;
+
+        let hash = source
+            .map(|source| source.hash())
+            .unwrap_or_else(|| node_hash(&format!("{:#X}/skeleton", self.hash)));
+
+        let skeleton_node = model_node.create_with_hash(CastId::Skeleton, hash);
+
+        if let Some(source) = source {
+            for property in source.properties() {
+                copy_property(skeleton_node, property);
+            }
+
+            for child in source.children() {
+                let (nodes, count) = match child.identifier() {
+                    CastId::Bone => (&sources.bones, skeleton.bones.len()),
+                    CastId::IKHandle => (&sources.ik_handles, skeleton.ik_handles.len()),
+                    CastId::Constraint => (&sources.constraints, skeleton.constraints.len()),
+                    _ => {
+                        copy_node(skeleton_node, child);
+                        continue;
+                    }
+                };
+
+                // Nodes the reader skipped are kept, nodes whose part was removed are dropped.
+                match index_of(nodes, child) {
+                    Some(index) if index < count => match child.identifier() {
+                        CastId::Bone => self.write_bone(skeleton_node, index, Some(child)),
+                        CastId::IKHandle => self.write_ik_handle(skeleton_node, index, Some(child)),
+                        _ => self.write_constraint(skeleton_node, index, Some(child)),
+                    },
+                    Some(_) => {}
+                    None => copy_node(skeleton_node, child),
+                }
+            }
+        }
+
+        for index in sources.bones.len()..skeleton.bones.len() {
+            self.write_bone(skeleton_node, index, None);
+        }
+
+        for index in sources.ik_handles.len()..skeleton.ik_handles.len() {
+            self.write_ik_handle(skeleton_node, index, None);
+        }
+
+        for index in sources.constraints.len()..skeleton.constraints.len() {
+            self.write_constraint(skeleton_node, index, None);
+        }
+    }
+
+    fn write_bone(&self, skeleton_node: &mut CastNode, index: usize, source: Option<&CastNode>) {
+        let bone = &self.model.skeleton.bones[index];
+
+        let bone_node = skeleton_node.create_with_hash(CastId::Bone, self.bone_hashes[index]);
+
+        let mut properties = Properties::new(source);
+
+        if let Some(name) = &bone.name {
+            properties.set("n", Values::String(name.clone()));
+        }
+
+        // Root bones are stored with a parent of -1.
+        properties.optional(
+            "p",
+            bone.parent != -1,
+            Values::Integers(CastPropertyId::Integer32, vec![bone.parent as u32 as u64]),
+        );
+        properties.optional(
+            "ssc",
+            bone.segment_scale_compensate,
+            Values::Integers(
+                CastPropertyId::Byte,
+                vec![bone.segment_scale_compensate as u64],
+            ),
+        );
+
+        // Transforms the source left out are derived when reading, so they're only written for new bones.
+        properties.optional(
+            "lp",
+            source.is_none(),
+            Values::Vector3(vec![bone.local_position]),
+        );
+        properties.optional(
+            "lr",
+            source.is_none(),
+            Values::Vector4(vec![rotation(bone.local_rotation)]),
+        );
+        properties.optional(
+            "wp",
+            source.is_none(),
+            Values::Vector3(vec![bone.world_position]),
+        );
+        properties.optional(
+            "wr",
+            source.is_none(),
+            Values::Vector4(vec![rotation(bone.world_rotation)]),
+        );
+        properties.optional(
+            "s",
+            bone.local_scale != Vector3::new(1.0, 1.0, 1.0),
+            Values::Vector3(vec![bone.local_scale]),
+        );
+
+        properties.write(bone_node, |name| {
+            matches!(name, "n" | "p" | "ssc" | "lp" | "lr" | "wp" | "wr" | "s")
+        });
+    }
+
+    fn write_ik_handle(
+        &self,
+        skeleton_node: &mut CastNode,
+        index: usize,
+        source: Option<&CastNode>,
+    ) {
+        let ik_handle = &self.model.skeleton.ik_handles[index];
+
+        let hash = source
+            .map(|source| source.hash())
+            .unwrap_or_else(|| node_hash(&format!("{:#X}/ik_handle[{index}]", self.hash)));
+
+        let ik_handle_node = skeleton_node.create_with_hash(CastId::IKHandle, hash);
+
+        let mut properties = Properties::new(source);
+
+        if let Some(name) = &ik_handle.name {
+            properties.set("n", Values::String(name.clone()));
+        }
+
+        let bones = [
+            ("sb", Some(ik_handle.start_bone)),
+            ("eb", Some(ik_handle.end_bone)),
+            ("tb", ik_handle.target_bone),
+            ("pvb", ik_handle.pole_vector_bone),
+            ("pb", ik_handle.pole_bone),
+        ];
+
+        for (name, bone) in bones {
+            if let Some(hash) = bone.and_then(|bone| self.bone_hashes.get(bone)) {
+                properties.set(
+                    name,
+                    Values::Integers(CastPropertyId::Integer64, vec![*hash]),
+                );
+            }
+        }
+
+        properties.optional(
+            "tr",
+            ik_handle.use_target_rotation,
+            Values::Integers(
+                CastPropertyId::Byte,
+                vec![ik_handle.use_target_rotation as u64],
+            ),
+        );
+
+        properties.write(ik_handle_node, |name| {
+            matches!(name, "n" | "sb" | "eb" | "tb" | "pvb" | "pb" | "tr")
+        });
+    }
+
+    fn write_constraint(
+        &self,
+        skeleton_node: &mut CastNode,
+        index: usize,
+        source: Option<&CastNode>,
+    ) {
+        let constraint = &self.model.skeleton.constraints[index];
+
+        let hash = source
+            .map(|source| source.hash())
+            .unwrap_or_else(|| node_hash(&format!("{:#X}/constraint[{index}]", self.hash)));
+
+        let constraint_node = skeleton_node.create_with_hash(CastId::Constraint, hash);
+
+        let mut properties = Properties::new(source);
+
+        if let Some(name) = &constraint.name {
+            properties.set("n", Values::String(name.clone()));
+        }
+
+        let constraint_type = match constraint.constraint_type {
+            ConstraintType::Point => "pt",
+            ConstraintType::Orient => "or",
+            ConstraintType::Scale => "sc",
+        };
+
+        properties.set("ct", Values::String(constraint_type.to_string()));
+
+        for (name, bone) in [
+            ("cb", constraint.constraint_bone),
+            ("tb", constraint.target_bone),
+        ] {
+            if let Some(hash) = self.bone_hashes.get(bone) {
+                properties.set(
+                    name,
+                    Values::Integers(CastPropertyId::Integer64, vec![*hash]),
+                );
+            }
+        }
+
+        for (name, value) in [
+            ("mo", constraint.maintain_offset),
+            ("sx", constraint.skip_x),
+            ("sy", constraint.skip_y),
+            ("sz", constraint.skip_z),
+        ] {
+            properties.optional(
+                name,
+                value,
+                Values::Integers(CastPropertyId::Byte, vec![value as u64]),
+            );
+        }
+
+        properties.write(constraint_node, |name| {
+            matches!(name, "n" | "ct" | "cb" | "tb" | "mo" | "sx" | "sy" | "sz")
+        });
+    }
+
+    fn write_material(&self, model_node: &mut CastNode, index: usize, source: Option<&CastNode>) {
+        let material = &self.model.materials[index];
+
+        let material_node =
+            model_node.create_with_hash(CastId::Material, self.material_hashes[index]);
+
+        let mut properties = Properties::new(source);
+
+        properties.optional(
+            "n",
+            !material.name.is_empty(),
+            Values::String(material.name.clone()),
+        );
+
+        // The shading type isn't part of the model, new materials use the default.
+        if source.is_none() {
+            properties.set("t", Values::String("pbr".to_string()));
+        }
+
+        for texture in &material.textures {
+            properties.set(
+                texture.texture_alias.clone(),
+                Values::Integers(CastPropertyId::Integer64, vec![texture.texture_hash]),
+            );
+        }
+
+        properties.write(material_node, is_material_slot);
+
+        // Slots sharing a texture share its file node.
+        let mut file_hashes = HashSet::new();
+
+        for child in source.iter().flat_map(|source| source.children()) {
+            let texture = material
+                .textures
+                .iter()
+                .find(|texture| texture.texture_hash == child.hash());
+
+            match texture {
+                Some(texture)
+                    if matches!(child.identifier(), CastId::File)
+                        && file_hashes.insert(texture.texture_hash) =>
+                {
+                    write_file(material_node, texture, Some(child));
+                }
+                _ => copy_node(material_node, child),
+            }
+        }
+
+        for texture in &material.textures {
+            if file_hashes.insert(texture.texture_hash) {
+                write_file(material_node, texture, None);
+            }
+        }
+    }
+
+    fn write_mesh(&self, model_node: &mut CastNode, index: usize, source: Option<&CastNode>) {
+        let mesh = &self.model.meshes[index];
+        let vertices = &mesh.vertices;
+
+        let mesh_node = model_node.create_with_hash(CastId::Mesh, self.mesh_hashes[index]);
+
+        let mut properties = Properties::new(source);
+
+        if let Some(name) = &mesh.name {
+            properties.set("n", Values::String(name.clone()));
+        }
+
+        let positions: Vec<Vector3> = (0..vertices.len())
+            .map(|vertex| vertices.vertex(vertex).position())
+            .collect();
+        let normals: Vec<Vector3> = (0..vertices.len())
+            .map(|vertex| vertices.vertex(vertex).normal())
+            .collect();
+
+        let has_normals = normals.iter().any(|normal| *normal != Vector3::default());
+
+        properties.optional("vp", !positions.is_empty(), Values::Vector3(positions));
+        properties.optional("vn", has_normals, Values::Vector3(normals));
+
+        let uv_layers = vertices.uv_layers();
+
+        properties.optional(
+            "ul",
+            uv_layers > 0,
+            Values::Integers(CastPropertyId::Byte, vec![uv_layers as u64]),
+        );
+
+        for layer in 0..uv_layers {
+            let uvs = (0..vertices.len())
+                .map(|vertex| vertices.vertex(vertex).uv(layer))
+                .collect();
+
+            properties.set(format!("u{layer}"), Values::Vector2(uvs));
+        }
+
+        let color_layers = vertices.colors();
+
+        // Without a count the layers are counted when reading, which gives the same number.
+        properties.optional(
+            "cl",
+            source.is_none() && color_layers > 0,
+            Values::Integers(CastPropertyId::Byte, vec![color_layers as u64]),
+        );
+
+        let single_layer = source.is_some_and(|source| {
+            source.property("vc").is_some() && source.property("c0").is_none()
+        });
+
+        for layer in 0..color_layers {
+            let colors = (0..vertices.len())
+                .map(|vertex| {
+                    let color = vertices.vertex(vertex).color(layer);
+
+                    [color.r, color.g, color.b, color.a]
+                })
+                .collect();
+
+            let name = if layer == 0 && single_layer {
+                "vc".to_string()
+            } else {
+                format!("c{layer}")
+            };
+
+            properties.set(name, Values::Colors(colors));
+        }
+
+        let maximum_influence = vertices.maximum_influence();
+
+        properties.optional(
+            "mi",
+            maximum_influence > 0,
+            Values::Integers(CastPropertyId::Byte, vec![maximum_influence as u64]),
+        );
+
+        if maximum_influence > 0 {
+            let weights: Vec<_> = (0..vertices.len())
+                .flat_map(|vertex| {
+                    (0..maximum_influence)
+                        .map(move |influence| vertices.vertex(vertex).weight(influence))
+                })
+                .collect();
+
+            properties.set(
+                "wb",
+                Values::Integers(
+                    CastPropertyId::Integer32,
+                    weights.iter().map(|weight| weight.bone as u64).collect(),
+                ),
+            );
+            properties.set(
+                "wv",
+                Values::Float(weights.iter().map(|weight| weight.value).collect()),
+            );
+        }
+
+        // Faces were flipped when read, so they're flipped back.
+        let faces: Vec<u64> = mesh
+            .faces
+            .iter()
+            .flat_map(|face| [face.i3, face.i2, face.i1])
+            .map(u64::from)
+            .collect();
+
+        properties.optional(
+            "f",
+            !faces.is_empty(),
+            Values::Integers(CastPropertyId::Integer32, faces),
+        );
+
+        if let Some(hash) = mesh
+            .material
+            .and_then(|material| self.material_hashes.get(material))
+        {
+            properties.set(
+                "m",
+                Values::Integers(CastPropertyId::Integer64, vec![*hash]),
+            );
+        }
+
+        properties.write(mesh_node, |name| {
+            let layer = |prefix: char, count: usize| {
+                name.strip_prefix(prefix)
+                    .and_then(|layer| layer.parse::<usize>().ok())
+                    .is_some_and(|layer| layer < count)
+            };
+
+            matches!(
+                name,
+                "n" | "vp" | "vn" | "ul" | "cl" | "vc" | "mi" | "f" | "m"
+            ) || (maximum_influence > 0 && matches!(name, "wb" | "wv"))
+                || layer('u', uv_layers)
+                || layer('c', color_layers)
+        });
+
+        if let Some(source) = source {
+            for child in source.children() {
+                copy_node(mesh_node, child);
+            }
+        }
+    }
+
+    /// Writes the target shape of a blend shape, a mesh holding the moved positions of the base mesh.
+    fn write_target(
+        &self,
+        model_node: &mut CastNode,
+        mesh_index: usize,
+        index: usize,
+        source: Option<&CastNode>,
+    ) {
+        let mesh = &self.model.meshes[mesh_index];
+        let blend_shape = &mesh.blend_shapes[index];
+
+        let target_node =
+            model_node.create_with_hash(CastId::Mesh, self.target_hashes[mesh_index][index]);
+
+        let mut properties = Properties::new(source);
+
+        // Targets read without a name were named after their blend shape node.
+        properties.optional(
+            "n",
+            source.is_none(),
+            Values::String(blend_shape.name.clone()),
+        );
+
+        let base: Vec<Vector3> = (0..mesh.vertices.len())
+            .map(|vertex| mesh.vertices.vertex(vertex).position())
+            .collect();
+        let deltas = (0..base.len())
+            .map(|vertex| {
+                blend_shape
+                    .vertex_deltas
+                    .get(&(vertex as u32))
+                    .copied()
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        properties.set("vp", Values::BlendTarget(base, deltas));
+
+        properties.write(target_node, |name| matches!(name, "n" | "vp"));
+
+        if let Some(source) = source {
+            for child in source.children() {
+                copy_node(target_node, child);
+            }
+        }
+    }
+
+    /// Writes a blend shape node holding the given blend shapes of a mesh.
+    fn write_blend_shape(
+        &self,
+        model_node: &mut CastNode,
+        mesh_index: usize,
+        indices: &[usize],
+        source: Option<&CastNode>,
+    ) {
+        let mesh = &self.model.meshes[mesh_index];
+
+        let hash = source.map(|source| source.hash()).unwrap_or_else(|| {
+            node_hash(&format!(
+                "{:#X}/mesh[{mesh_index}]/blend_shapes[{}]",
+                self.hash, indices[0]
+            ))
+        });
+
+        let blend_shape_node = model_node.create_with_hash(CastId::BlendShape, hash);
+
+        let mut properties = Properties::new(source);
+
+        if let (None, Some(name)) = (source, &mesh.name) {
+            properties.set("n", Values::String(name.clone()));
+        }
+
+        let scales: Vec<f32> = indices
+            .iter()
+            .map(|index| mesh.blend_shapes[*index].target_scale)
+            .collect();
+
+        properties.set(
+            "b",
+            Values::Integers(
+                CastPropertyId::Integer64,
+                vec![self.mesh_hashes[mesh_index]],
+            ),
+        );
+        properties.set(
+            "t",
+            Values::Integers(
+                CastPropertyId::Integer64,
+                indices
+                    .iter()
+                    .map(|index| self.target_hashes[mesh_index][*index])
+                    .collect(),
+            ),
+        );
+        properties.optional(
+            "ts",
+            scales.iter().any(|scale| *scale != 1.0),
+            Values::Float(scales),
+        );
+
+        properties.write(blend_shape_node, |name| matches!(name, "b" | "t" | "ts"));
+
+        if let Some(source) = source {
+            for child in source.children() {
+                copy_node(blend_shape_node, child);
+            }
+        }
+    }
+
+    fn write_hair(&self, model_node: &mut CastNode, index: usize, source: Option<&CastNode>) {
+        let hair = &self.model.hairs[index];
+
+        let hash = source
+            .map(|source| source.hash())
+            .unwrap_or_else(|| node_hash(&format!("{:#X}/hair[{index}]", self.hash)));
+
+        let hair_node = model_node.create_with_hash(CastId::Hair, hash);
+
+        let mut properties = Properties::new(source);
+
+        if let Some(name) = &hair.name {
+            properties.set("n", Values::String(name.clone()));
+        }
+
+        properties.optional(
+            "se",
+            !hair.segments.is_empty(),
+            Values::Integers(
+                CastPropertyId::Integer32,
+                hair.segments.iter().map(|count| *count as u64).collect(),
+            ),
+        );
+        properties.optional(
+            "pt",
+            !hair.particles.is_empty(),
+            Values::Vector3(hair.particles.clone()),
+        );
+
+        if let Some(hash) = hair
+            .material
+            .and_then(|material| self.material_hashes.get(material))
+        {
+            properties.set(
+                "m",
+                Values::Integers(CastPropertyId::Integer64, vec![*hash]),
+            );
+        }
+
+        properties.write(hair_node, |name| matches!(name, "n" | "se" | "pt" | "m"));
+
+        if let Some(source) = source {
+            for child in source.children() {
+                copy_node(hair_node, child);
+            }
+        }
+    }
+
+    /// The mesh and blend shape a target mesh node was read as.
+    fn target_of(&self, node: &CastNode) -> Option<(usize, usize)> {
+        self.sources
+            .blend_shapes
+            .iter()
+            .enumerate()
+            .find_map(|(mesh_index, targets)| {
+                targets
+                    .iter()
+                    .position(|(_, target)| std::ptr::eq(*target, node))
+                    .map(|index| (mesh_index, index))
+            })
+    }
+
+    /// The mesh a blend shape node was read for, and which of its blend shapes are still in the model.
+    fn blend_shape_of(&self, node: &CastNode) -> Option<(usize, Vec<usize>)> {
+        self.sources
+            .blend_shapes
+            .iter()
+            .enumerate()
+            .find(|(_, targets)| {
+                targets
+                    .iter()
+                    .any(|(blend_shape, _)| std::ptr::eq(*blend_shape, node))
+            })
+            .map(|(mesh_index, targets)| {
+                let indices = targets
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, (blend_shape, _))| {
+                        std::ptr::eq(*blend_shape, node) && self.has_blend_shape(mesh_index, *index)
+                    })
+                    .map(|(index, _)| index)
+                    .collect();
+
+                (mesh_index, indices)
+            })
+    }
+
+    fn has_blend_shape(&self, mesh_index: usize, index: usize) -> bool {
+        self.model
+            .meshes
+            .get(mesh_index)
+            .is_some_and(|mesh| index < mesh.blend_shapes.len())
+    }
+}
+
+fn write_file(
+    material_node: &mut CastNode,
+    texture: &MaterialTextureRef,
+    source: Option<&CastNode>,
+) {
+    let file_node = material_node.create_with_hash(CastId::File, texture.texture_hash);
+
+    let mut properties = Properties::new(source);
+
+    properties.set("p", Values::String(texture.file_name.clone()));
+    properties.write(file_node, |name| name == "p");
+
+    if let Some(source) = source {
+        for child in source.children() {
+            copy_node(file_node, child);
+        }
+    }
+}
+
+/// Whether the material reader treats the given property as a texture slot.
+fn is_material_slot(name: &str) -> bool {
+    MATERIAL_SLOTS.iter().any(|(slot, _)| *slot == name)
+        || name
+            .strip_prefix("extra")
+            .is_some_and(|index| index.parse::<usize>().is_ok())
+}
+
+/// The properties of a node written from the model, in the order of the node it was read from.
+struct Properties<'a> {
+    source: Option<&'a CastNode>,
+    values: Vec<(String, Values)>,
+}
+
+impl<'a> Properties<'a> {
+    fn new(source: Option<&'a CastNode>) -> Self {
+        Self {
+            source,
+            values: Vec::new(),
+        }
+    }
+
+    fn set(&mut self, name: impl Into<String>, values: Values) {
+        self.values.push((name.into(), values));
+    }
+
+    /// Sets a property only if the source had it, or if leaving it out would read back differently.
+    fn optional(&mut self, name: &str, needed: bool, values: Values) {
+        if needed
+            || self
+                .source
+                .is_some_and(|source| source.property(name).is_some())
+        {
+            self.set(name, values);
+        }
+    }
+
+    /// Writes the properties in the order of the source, followed by new ones.
+    ///
+    /// Source properties the model doesn't set are copied, unless they're owned by the model and were removed.
+    fn write(mut self, node: &mut CastNode, owned: impl Fn(&str) -> bool) {
+        for property in self.source.iter().flat_map(|source| source.properties()) {
+            let name = property.name();
+
+            match self
+                .values
+                .iter()
+                .position(|(value_name, _)| value_name == name)
+            {
+                Some(index) => {
+                    let (name, values) = self.values.remove(index);
+
+                    values.write(node, &name, Some(property));
+                }
+                None if !owned(name) => copy_property(node, property),
+                None => {}
+            }
+        }
+
+        for (name, values) in self.values {
+            values.write(node, &name, None);
+        }
+    }
+}
+
+/// The values of a property, as the model holds them.
+enum Values {
+    String(String),
+    /// Integers, stored with the given width unless the source used another that fits
+    Integers(CastPropertyId, Vec<u64>),
+    Float(Vec<f32>),
+    Vector2(Vec<Vector2>),
+    Vector3(Vec<Vector3>),
+    Vector4(Vec<Vector4>),
+    /// Packed RGBA colors, stored as float vectors when the source used them
+    Colors(Vec<[u8; 4]>),
+    /// The base positions of a mesh and the delta of each vertex, stored as the moved positions
+    BlendTarget(Vec<Vector3>, Vec<Vector3>),
+}
+
+impl Values {
+    /// Writes the values, copying the source property instead when it reads back as the same values.
+    fn write(&self, node: &mut CastNode, name: &str, source: Option<&CastProperty>) {
+        if let Some(source) = source.filter(|source| self.read_from(source)) {
+            copy_property(node, source);
+            return;
+        }
+
+        match self {
+            Self::String(value) => {
+                node.create_property(CastPropertyId::String, name)
+                    .push(value.clone());
+            }
+            Self::Integers(width, values) => {
+                let width = source
+                    .map(|source| source.id())
+                    .filter(|id| integer_width(*id).is_some())
+                    .unwrap_or(*width);
+
+                write_integers(node, name, width, values);
+            }
+            Self::Float(values) => {
+                let property = node.create_property(CastPropertyId::Float, name);
+
+                for value in values {
+                    property.push(*value);
+                }
+            }
+            Self::Vector2(values) => {
+                let property = node.create_property(CastPropertyId::Vector2, name);
+
+                for value in values {
+                    property.push(*value);
+                }
+            }
+            Self::Vector3(values) => {
+                let property = node.create_property(CastPropertyId::Vector3, name);
+
+                for value in values {
+                    property.push(*value);
+                }
+            }
+            Self::Vector4(values) => {
+                let property = node.create_property(CastPropertyId::Vector4, name);
+
+                for value in values {
+                    property.push(*value);
+                }
+            }
+            Self::Colors(colors) => {
+                if source.is_some_and(|source| matches!(source.id(), CastPropertyId::Vector4)) {
+                    let property = node.create_property(CastPropertyId::Vector4, name);
+
+                    for [r, g, b, a] in colors {
+                        let channel = |value: u8| value as f32 / 255.0;
+
+                        property.push(Vector4::new(
+                            channel(*r),
+                            channel(*g),
+                            channel(*b),
+                            channel(*a),
+                        ));
+                    }
+                } else {
+                    let property = node.create_property(CastPropertyId::Integer32, name);
+
+                    for color in colors {
+                        property.push(u32::from_le_bytes(*color));
+                    }
+                }
+            }
+            Self::BlendTarget(base, deltas) => {
+                let property = node.create_property(CastPropertyId::Vector3, name);
+
+                for (base, delta) in base.iter().zip(deltas) {
+                    property.push(Vector3::new(
+                        base.x + delta.x,
+                        base.y + delta.y,
+                        base.z + delta.z,
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Whether the model reader gets these values from the given property.
+    fn read_from(&self, property: &CastProperty) -> bool {
+        let id = property.id();
+
+        match self {
+            Self::String(value) => {
+                matches!(id, CastPropertyId::String)
+                    && property.values::<String>().next().as_ref() == Some(value)
+            }
+            Self::Integers(_, values) => {
+                integer_width(id).is_some() && integers(property) == *values
+            }
+            Self::Float(values) => {
+                matches!(id, CastPropertyId::Float)
+                    && property.values::<f32>().eq(values.iter().copied())
+            }
+            Self::Vector2(values) => {
+                matches!(id, CastPropertyId::Vector2)
+                    && property.values::<Vector2>().eq(values.iter().copied())
+            }
+            Self::Vector3(values) => {
+                matches!(id, CastPropertyId::Vector3)
+                    && property.values::<Vector3>().eq(values.iter().copied())
+            }
+            Self::Vector4(values) => {
+                matches!(id, CastPropertyId::Vector4)
+                    && property.values::<Vector4>().eq(values.iter().copied())
+            }
+            Self::Colors(colors) => {
+                matches!(id, CastPropertyId::Vector4 | CastPropertyId::Integer32)
+                    && cast_model::color_values(property)
+                        .iter()
+                        .map(|color| [color.r, color.g, color.b, color.a])
+                        .eq(colors.iter().copied())
+            }
+            Self::BlendTarget(base, deltas) => {
+                if !matches!(id, CastPropertyId::Vector3) {
+                    return false;
+                }
+
+                // Targets are read as the deltas of the vertices that move, everything else is ignored.
+                let mut targets = property.values::<Vector3>();
+
+                base.iter().zip(deltas).all(|(base, delta)| {
+                    let read = targets
+                        .next()
+                        .map(|target| {
+                            Vector3::new(target.x - base.x, target.y - base.y, target.z - base.z)
+                        })
+                        .filter(|delta| {
+                            delta.x.abs() > f32::EPSILON
+                                || delta.y.abs() > f32::EPSILON
+                                || delta.z.abs() > f32::EPSILON
+                        })
+                        .unwrap_or_default();
+
+                    read == *delta
+                })
+            }
+        }
+    }
+}
+
+/// The byte width of an integer property type.
+fn integer_width(id: CastPropertyId) -> Option<usize> {
+    match id {
+        CastPropertyId::Byte => Some(1),
+        CastPropertyId::Short => Some(2),
+        CastPropertyId::Integer32 => Some(4),
+        CastPropertyId::Integer64 => Some(8),
+        _ => None,
+    }
+}
+
+/// Reads an integer property of any width.
+fn integers(property: &CastProperty) -> Vec<u64> {
+    match property.id() {
+        CastPropertyId::Integer64 => property.values::<u64>().collect(),
+        _ => cast_model::integer_values(property)
+            .into_iter()
+            .map(u64::from)
+            .collect(),
+    }
+}
+
+/// Writes integers with the given width, or the smallest wider one they fit in.
+fn write_integers(node: &mut CastNode, name: &str, width: CastPropertyId, values: &[u64]) {
+    let maximum = values.iter().copied().max().unwrap_or_default();
+
+    let width = match integer_width(width).unwrap_or(4) {
+        1 if maximum <= u8::MAX as u64 => CastPropertyId::Byte,
+        1 | 2 if maximum <= u16::MAX as u64 => CastPropertyId::Short,
+        1..=4 if maximum <= u32::MAX as u64 => CastPropertyId::Integer32,
+        _ => CastPropertyId::Integer64,
+    };
+
+    let property = node.create_property(width, name);
+
+    for value in values {
+        match width {
+            CastPropertyId::Byte => property.push(*value as u8),
+            CastPropertyId::Short => property.push(*value as u16),
+            CastPropertyId::Integer32 => property.push(*value as u32),
+            _ => property.push(*value),
+        }
+    }
+}
+
+/// Copies a node, its properties and children as they are.
+fn copy_node(parent: &mut CastNode, source: &CastNode) {
+    let node = parent.create_with_hash(source.identifier(), source.hash());
+
+    for property in source.properties() {
+        copy_property(node, property);
+    }
+
+    for child in source.children() {
+        copy_node(node, child);
+    }
+}
+
+/// Copies a property with the type it was read with.
+fn copy_property(node: &mut CastNode, source: &CastProperty) {
+    let property = node.create_property(source.id(), source.name());
+
+    match source.id() {
+        CastPropertyId::Byte => source.values::<u8>().for_each(|value| property.push(value)),
+        CastPropertyId::Short => source
+            .values::<u16>()
+            .for_each(|value| property.push(value)),
+        CastPropertyId::Integer32 => source
+            .values::<u32>()
+            .for_each(|value| property.push(value)),
+        CastPropertyId::Integer64 => source
+            .values::<u64>()
+            .for_each(|value| property.push(value)),
+        CastPropertyId::Float => source
+            .values::<f32>()
+            .for_each(|value| property.push(value)),
+        CastPropertyId::Double => source
+            .values::<f64>()
+            .for_each(|value| property.push(value)),
+        CastPropertyId::String => source
+            .values::<String>()
+            .for_each(|value| property.push(value)),
+        CastPropertyId::Vector2 => source
+            .values::<Vector2>()
+            .for_each(|value| property.push(value)),
+        CastPropertyId::Vector3 => source
+            .values::<Vector3>()
+            .for_each(|value| property.push(value)),
+        CastPropertyId::Vector4 => source
+            .values::<Vector4>()
+            .for_each(|value| property.push(value)),
+    }
+}
+
+/// The position of a node among the nodes a model was read from.
+fn index_of(nodes: &[&CastNode], node: &CastNode) -> Option<usize> {
+    nodes.iter().position(|other| std::ptr::eq(*other, node))
+}
+
+fn rotation(rotation: Quaternion) -> Vector4 {
+    Vector4::new(rotation.x, rotation.y, rotation.z, rotation.w)
+}
+
+/// A stable node hash for the given place in the model, using 64 bit FNV-1a.
+fn node_hash(path: &str) -> u64 {
+    path.bytes().fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01B3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cast_model::process_model_node;
+    use crate::cast_scan::{raw_difference, read_raw_nodes};
+    use crate::test_cast;
+    use std::io::Cursor;
+
+    fn rewrite(buffer: &[u8], edit: impl Fn(&mut Model)) -> Vec<u8> {
+        let file = CastFile::read(&mut Cursor::new(buffer)).unwrap();
+
+        let rewritten = rewrite_models(&file, |_, _, model_node| {
+            let mut model = process_model_node(model_node)?;
+
+            edit(&mut model);
+
+            Some(model)
+        });
+
+        let mut written = Vec::new();
+
+        write_cast(&rewritten, &mut written).unwrap();
+        written
+    }
+
+    #[test]
+    fn rewrites_unchanged_models_as_read() {
+        for buffer in [test_cast::triangle_model(), test_cast::blend_shape_model()] {
+            let written = rewrite(&buffer, |_| {});
+
+            let expected = read_raw_nodes(&buffer).unwrap();
+            let actual = read_raw_nodes(&written).unwrap();
+
+            assert_eq!(raw_difference(&expected, &actual, ""), None);
+        }
+    }
+
+    #[test]
+    fn rewrites_changed_blend_shapes() {
+        let written = rewrite(&test_cast::blend_shape_model(), |model| {
+            let blend_shape = &mut model.meshes[0].blend_shapes[0];
+
+            blend_shape.target_scale = 1.0;
+            blend_shape
+                .vertex_deltas
+                .insert(0, Vector3::new(0.0, 0.0, 2.0));
+        });
+
+        let file = CastFile::read(&mut Cursor::new(&written)).unwrap();
+        let root = &file.roots()[0];
+
+        // The metadata node isn't part of the model, and is kept as it was.
+        assert!(root.child_by_hash(0x2).is_some());
+
+        let model_node = root.children_of_type(CastId::Model).next().unwrap();
+        let model = process_model_node(model_node).unwrap();
+
+        assert_eq!(model.meshes.len(), 1);
+
+        let blend_shape = &model.meshes[0].blend_shapes[0];
+
+        assert_eq!(blend_shape.name, "raise");
+        assert_eq!(blend_shape.target_scale, 1.0);
+        assert_eq!(blend_shape.vertex_deltas.len(), 2);
+        assert_eq!(
+            blend_shape.vertex_deltas.get(&0),
+            Some(&Vector3::new(0.0, 0.0, 2.0))
+        );
+        assert!(model_node.child_by_hash(0x41).is_some());
+    }
+
+    #[test]
+    fn writes_models_without_a_source() {
+        let file = CastFile::read(&mut Cursor::new(test_cast::triangle_model())).unwrap();
+        let model_node = file.roots()[0]
+            .children_of_type(CastId::Model)
+            .next()
+            .unwrap();
+        let model = process_model_node(model_node).unwrap();
+
+        let mut written = Vec::new();
+
+        write_cast(&model_file(&model, None), &mut written).unwrap();
+
+        let file = CastFile::read(&mut Cursor::new(&written)).unwrap();
+        let model_node = file.roots()[0]
+            .children_of_type(CastId::Model)
+            .next()
+            .unwrap();
+        let model = process_model_node(model_node).unwrap();
+
+        assert_eq!(model.skeleton.bones.len(), 2);
+        assert_eq!(model.materials.len(), 1);
+        assert_eq!(model.materials[0].name, "skin");
+        assert_eq!(model.materials[0].textures[0].file_name, "skin_c.png");
+
+        let mesh = &model.meshes[0];
+
+        assert_eq!(mesh.name.as_deref(), Some("body"));
+        assert_eq!(mesh.material, Some(0));
+        assert_eq!(mesh.vertices.len(), 3);
+        assert_eq!(mesh.faces.len(), 1);
+
+        let uv = mesh.vertices.vertex(1).uv(0);
+
+        assert_eq!((uv.x, uv.y), (1.0, 0.0));
+
+        // Once written, the file reads back and rewrites like any other.
+        let expected = read_raw_nodes(&written).unwrap();
+        let actual = read_raw_nodes(&rewrite(&written, |_| {})).unwrap();
+
+        assert_eq!(raw_difference(&expected, &actual, ""), None);
+    }
+}
@@ -0,0 +1,417 @@
+use porter_animation::AnimationFileType;
+use porter_app::{AssetManager as _, Settings};
+use porter_cast::CastFile;
+use porter_model::ModelFileType;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::asset_manager::{AssetManager, is_cast_file};
+use crate::{cast_model, cast_scan, cast_validate, cast_write};
+
+const VALIDATE_USAGE: &str = "\
+Usage: cast_viewer validate <files|directories>...
+
+Lists the issues found in every cast file, and exits with a nonzero code when any are errors.";
+
+const EXPORT_USAGE: &str = "\
+Usage: cast_viewer export <files|directories>... [options]
+
+Options:
+  --format <list>   Comma separated model formats: obj, smd, xnalara, xmodel_export, cast, maya, fbx
+                    Animations are only exported when cast is listed. Defaults to the saved settings.
+  --out <dir>       Output directory. Defaults to the saved settings.
+  --scale <factor>  Scale applied to every exported asset.";
+
+const ROUNDTRIP_USAGE: &str = "\
+Usage: cast_viewer roundtrip <files|directories>... [options]
+
+Reads every cast file and writes it again, checking that the nodes, hashes and property values are unchanged,
+both when writing the file as read and when writing each model as the viewer processed it. Exits with a nonzero
+code when anything differs.
+
+Options:
+  --out <dir>       Also saves the files with their models rewritten to this directory.";
+
+/// Model formats accepted by `--format`, by name
+const MODEL_FORMATS: [(&str, ModelFileType); 7] = [
+    ("obj", ModelFileType::Obj),
+    ("smd", ModelFileType::Smd),
+    ("xnalara", ModelFileType::XnaLara),
+    ("xmodel_export", ModelFileType::XModelExport),
+    ("cast", ModelFileType::Cast),
+    ("maya", ModelFileType::Maya),
+    ("fbx", ModelFileType::Fbx),
+];
+
+/// Arguments given to the export command
+struct ExportOptions {
+    inputs: Vec<PathBuf>,
+    formats: Option<Vec<String>>,
+    output_directory: Option<PathBuf>,
+    scale: Option<f32>,
+}
+
+/// Runs the command given on the command line, returning the process exit code, or none to open the window.
+pub fn run(app_name: &str, args: &[String]) -> Option<i32> {
+    let command: fn(&str, &[String]) -> i32 = match args.first().map(String::as_str) {
+        Some("export") => export,
+        Some("validate") => |_, args| validate(args),
+        Some("roundtrip") => |_, args| roundtrip(args),
+        _ => return None,
+    };
+
+    attach_console();
+
+    Some(command(app_name, &args[1..]))
+}
+
+/// Release builds on Windows don't get a console, so commands print to the one they were started from.
+#[cfg(target_os = "windows")]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole};
+
+    // Fails when started without a console, in which case there's nowhere to print to anyway.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn attach_console() {}
+
+/// Checks the given files and directories for broken data, returning the process exit code.
+fn validate(args: &[String]) -> i32 {
+    if args.is_empty() || args.iter().any(|arg| arg.starts_with("--")) {
+        eprintln!("{VALIDATE_USAGE}");
+        return 2;
+    }
+
+    let mut failures: Vec<(PathBuf, String)> = Vec::new();
+
+    let files = collect_cast_files(args.iter().map(PathBuf::from), &mut failures);
+
+    let mut errors = 0;
+    let mut warnings = 0;
+
+    for file_name in files {
+        let file = match cast_model::read_cast_file(&file_name) {
+            Ok(file) => file,
+            Err(e) => {
+                failures.push((file_name, e));
+                continue;
+            }
+        };
+
+        let issues = cast_validate::validate_cast_file(&file);
+
+        if issues.is_empty() {
+            continue;
+        }
+
+        println!("{}", file_name.display());
+
+        for issue in issues {
+            if issue.is_error() {
+                errors += 1;
+            } else {
+                warnings += 1;
+            }
+
+            println!("  {issue}");
+        }
+    }
+
+    for (file_name, error) in &failures {
+        println!("{}\n  Error: {error}", file_name.display());
+    }
+
+    errors += failures.len();
+
+    println!("{errors} errors, {warnings} warnings");
+
+    if errors > 0 { 1 } else { 0 }
+}
+
+/// Writes the given files and directories back out and compares them to what was read, returning the process exit code.
+fn roundtrip(args: &[String]) -> i32 {
+    let mut inputs = Vec::new();
+    let mut output_directory: Option<PathBuf> = None;
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => match args.next() {
+                Some(directory) => output_directory = Some(PathBuf::from(directory)),
+                None => {
+                    eprintln!("Missing value for {arg}\n\n{ROUNDTRIP_USAGE}");
+                    return 2;
+                }
+            },
+            _ if arg.starts_with("--") => {
+                eprintln!("Unknown option {arg}\n\n{ROUNDTRIP_USAGE}");
+                return 2;
+            }
+            _ => inputs.push(PathBuf::from(arg)),
+        }
+    }
+
+    if inputs.is_empty() {
+        eprintln!("{ROUNDTRIP_USAGE}");
+        return 2;
+    }
+
+    let mut failures: Vec<(PathBuf, String)> = Vec::new();
+
+    let files = collect_cast_files(inputs, &mut failures);
+
+    if let Some(output_directory) = &output_directory {
+        if let Err(e) = std::fs::create_dir_all(output_directory) {
+            eprintln!("Could not create output directory: {e}");
+            return 1;
+        }
+    }
+
+    for file_name in &files {
+        let file = match roundtrip_file(file_name) {
+            Ok(file) => file,
+            Err(e) => {
+                failures.push((file_name.clone(), e));
+                continue;
+            }
+        };
+
+        if let Some(output_directory) = &output_directory {
+            let output = output_directory.join(file_name.file_name().unwrap_or_default());
+
+            if let Err(e) = cast_write::write_cast_file(&file, &output) {
+                failures.push((file_name.clone(), e));
+            }
+        }
+    }
+
+    for (file_name, error) in &failures {
+        println!("{}\n  {error}", file_name.display());
+    }
+
+    println!("{} files, {} errors", files.len(), failures.len());
+
+    if failures.is_empty() { 0 } else { 1 }
+}
+
+/// Reads the given file, writes it as read and with every model rewritten, and reports the first difference found.
+fn roundtrip_file(file_name: &Path) -> Result<CastFile, String> {
+    let buffer = std::fs::read(file_name).map_err(|e| format!("Could not open: {e}"))?;
+
+    let file = CastFile::read(&mut Cursor::new(&buffer))
+        .map_err(|e| format!("Error loading file: {e}"))?;
+
+    let expected = cast_scan::read_raw_nodes(&buffer)?;
+
+    let mut written = Vec::new();
+
+    cast_write::write_cast(&file, &mut written)?;
+
+    if let Some(difference) =
+        cast_scan::raw_difference(&expected, &cast_scan::read_raw_nodes(&written)?, "")
+    {
+        return Err(format!("File differs after writing: {difference}"));
+    }
+
+    // Every model processed and written back should store the same nodes as the original.
+    let rewritten = cast_write::rewrite_models(&file, |_, _, model_node| {
+        cast_model::process_model_node(model_node)
+    });
+
+    let mut written = Vec::new();
+
+    cast_write::write_cast(&rewritten, &mut written)?;
+
+    if let Some(difference) =
+        cast_scan::raw_difference(&expected, &cast_scan::read_raw_nodes(&written)?, "")
+    {
+        return Err(format!("Models differ after rewriting them: {difference}"));
+    }
+
+    Ok(rewritten)
+}
+
+/// Loads and exports the given files and directories without a window, returning the process exit code.
+///
+/// Uses the saved settings of the app, overridden by the given options.
+fn export(app_name: &str, args: &[String]) -> i32 {
+    let options = match parse_export_options(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n\n{EXPORT_USAGE}");
+            return 2;
+        }
+    };
+
+    let mut settings = Settings::load(app_name);
+
+    settings.set_load_models(true);
+    settings.set_load_animations(true);
+    // The textures of every model are exported with it, materials on their own would only repeat them.
+    settings.set_load_materials(false);
+
+    if let Some(formats) = &options.formats {
+        for (name, file_type) in MODEL_FORMATS {
+            settings.set_model_file_type(file_type, formats.iter().any(|format| format == name));
+        }
+
+        settings.set_anim_file_type(
+            AnimationFileType::Cast,
+            formats.iter().any(|format| format == "cast"),
+        );
+    }
+
+    if let Some(output_directory) = options.output_directory {
+        settings.set_output_directory(output_directory);
+    }
+
+    if let Some(scale) = options.scale {
+        settings.set_auto_scale(true);
+        settings.set_custom_scale(Some(scale));
+    }
+
+    let mut failures: Vec<(PathBuf, String)> = Vec::new();
+
+    let files = collect_cast_files(options.inputs, &mut failures);
+
+    let manager = AssetManager::new();
+
+    if let Err(e) = manager.load_files(settings.clone(), files.clone()) {
+        eprintln!("Failed to load files: {e}");
+        return 1;
+    }
+
+    let loaded_files = manager.loaded_files();
+
+    for file_name in files.iter().filter(|file| !loaded_files.contains(*file)) {
+        failures.push((
+            file_name.clone(),
+            "No model or animation could be loaded".to_string(),
+        ));
+    }
+
+    let total = manager.assets_total();
+
+    println!(
+        "Exporting {total} assets from {} files to {}",
+        files.len(),
+        settings.output_directory().display()
+    );
+
+    let summary = manager.export_assets(&settings, (0..total).collect(), |_| {});
+
+    for failure in summary.failures {
+        failures.push((
+            failure.file_name,
+            format!("{}: {}", failure.name, failure.error),
+        ));
+    }
+
+    println!("Exported {} assets", summary.exported);
+
+    if summary.skipped > 0 {
+        println!(
+            "Skipped {} assets without an enabled export format",
+            summary.skipped
+        );
+    }
+
+    if failures.is_empty() {
+        return 0;
+    }
+
+    eprintln!("{} errors:", failures.len());
+
+    for (file_name, error) in failures {
+        eprintln!("  {}: {error}", file_name.display());
+    }
+
+    1
+}
+
+/// Expands the given inputs into cast files, recursing into directories, and records inputs that aren't either.
+fn collect_cast_files(
+    inputs: impl IntoIterator<Item = PathBuf>,
+    failures: &mut Vec<(PathBuf, String)>,
+) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    for input in inputs {
+        if input.is_dir() {
+            files.extend(
+                WalkDir::new(&input)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .map(|entry| entry.into_path())
+                    .filter(|path| path.is_file() && is_cast_file(path)),
+            );
+        } else if input.is_file() && is_cast_file(&input) {
+            files.push(input);
+        } else {
+            failures.push((input, "Not a cast file or directory".to_string()));
+        }
+    }
+
+    files
+}
+
+fn parse_export_options(args: &[String]) -> Result<ExportOptions, String> {
+    let mut options = ExportOptions {
+        inputs: Vec::new(),
+        formats: None,
+        output_directory: None,
+        scale: None,
+    };
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("Missing value for {arg}"))
+        };
+
+        match arg.as_str() {
+            "--format" => {
+                let formats: Vec<String> = value()?
+                    .split(',')
+                    .map(|format| format.trim().to_lowercase())
+                    .filter(|format| !format.is_empty())
+                    .collect();
+
+                if let Some(unknown) = formats.iter().find(|format| {
+                    !MODEL_FORMATS
+                        .iter()
+                        .any(|(name, _)| *name == format.as_str())
+                }) {
+                    return Err(format!("Unknown format {unknown}"));
+                }
+
+                options.formats = Some(formats);
+            }
+            "--out" => options.output_directory = Some(PathBuf::from(value()?)),
+            "--scale" => {
+                let scale = value()?;
+
+                options.scale = match scale.parse::<f32>() {
+                    Ok(scale) if scale > 0.0 => Some(scale),
+                    _ => return Err(format!("Invalid scale {scale}")),
+                };
+            }
+            _ if arg.starts_with("--") => return Err(format!("Unknown option {arg}")),
+            _ => options.inputs.push(PathBuf::from(arg)),
+        }
+    }
+
+    if options.inputs.is_empty() {
+        return Err("No files or directories given".to_string());
+    }
+
+    Ok(options)
+}
@@ -0,0 +1,297 @@
+use notify::{Config, PollWatcher, RecursiveMode, Watcher};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+use walkdir::WalkDir;
+
+use crate::asset_manager::is_cast_file;
+
+/// How long to wait between two checks of the watched files, when they can only be polled
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long events have to stop coming in before they're handled
+const SETTLE_TIME: Duration = Duration::from_millis(200);
+
+/// Modification time and size of every watched cast file
+type Snapshot = BTreeMap<PathBuf, (Option<SystemTime>, u64)>;
+
+/// Cast files that appeared, disappeared or were modified since the last change
+#[derive(Debug, Default)]
+pub struct FileChanges {
+    pub added: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
+    pub modified: Vec<PathBuf>,
+}
+
+impl FileChanges {
+    /// Whether or not nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Watches loaded files and directories for changes on a background thread, until dropped
+pub struct FileWatcher {
+    /// The source of change events, dropping it ends the thread
+    watcher: Option<Box<dyn Watcher + Send>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl FileWatcher {
+    /// Starts watching the given files and directories, calling `on_change` whenever a cast file under them changes.
+    ///
+    /// Uses the notifications of the operating system, and falls back to polling when they aren't available.
+    pub fn spawn(
+        roots: Vec<PathBuf>,
+        on_change: impl Fn(FileChanges) + Send + 'static,
+    ) -> Result<Self, String> {
+        let (sender, receiver) = mpsc::channel();
+
+        let watcher = match watch(&roots, notify::recommended_watcher(sender.clone())) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                eprintln!("Falling back to polling for file changes: {e}");
+
+                let config = Config::default().with_poll_interval(POLL_INTERVAL);
+
+                watch(&roots, PollWatcher::new(sender, config))?
+            }
+        };
+
+        let thread = std::thread::spawn(move || {
+            let mut previous = snapshot(&roots);
+
+            // Waits for the first event of a batch, ending once the watcher is dropped.
+            while let Ok(event) = receiver.recv() {
+                let mut events = vec![event];
+
+                // Saving a file sends several events, they're handled together once they settle.
+                while let Ok(event) = receiver.recv_timeout(SETTLE_TIME) {
+                    events.push(event);
+                }
+
+                let rescan = events.iter().any(|event| match event {
+                    Ok(event) => event.need_rescan(),
+                    // Events may have been missed, so everything is checked again.
+                    Err(_) => true,
+                });
+
+                let changes = if rescan {
+                    let current = snapshot(&roots);
+                    let changes = compare(&previous, &current);
+
+                    previous = current;
+                    changes
+                } else {
+                    let paths: BTreeSet<PathBuf> = events
+                        .into_iter()
+                        .flatten()
+                        .flat_map(|event| event.paths)
+                        .filter(|path| is_watched(&roots, path))
+                        .collect();
+
+                    refresh(&mut previous, &paths)
+                };
+
+                if !changes.is_empty() {
+                    on_change(changes);
+                }
+            }
+        });
+
+        Ok(Self {
+            watcher: Some(watcher),
+            thread: Some(thread),
+        })
+    }
+}
+
+impl fmt::Debug for FileWatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileWatcher").finish_non_exhaustive()
+    }
+}
+
+impl Drop for FileWatcher {
+    fn drop(&mut self) {
+        // Dropping the watcher disconnects the channel, so the thread returns once it handled what it had.
+        self.watcher = None;
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Watches every root with the given watcher.
+///
+/// Files are watched through their directory, so they're still followed when they're replaced instead of written to.
+fn watch<W: Watcher + Send + 'static>(
+    roots: &[PathBuf],
+    watcher: notify::Result<W>,
+) -> Result<Box<dyn Watcher + Send>, String> {
+    let mut watcher = watcher.map_err(|e| format!("Could not watch files: {e}"))?;
+
+    for root in roots {
+        let result = if root.is_dir() {
+            watcher.watch(root, RecursiveMode::Recursive)
+        } else {
+            let directory = root
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+                .unwrap_or(Path::new("."));
+
+            watcher.watch(directory, RecursiveMode::NonRecursive)
+        };
+
+        result.map_err(|e| format!("Could not watch {}: {e}", root.display()))?;
+    }
+
+    Ok(Box::new(watcher))
+}
+
+/// Whether the given path is, or is under, one of the roots
+fn is_watched(roots: &[PathBuf], path: &Path) -> bool {
+    roots
+        .iter()
+        .any(|root| path == root || (root.is_dir() && path.starts_with(root)))
+}
+
+/// Updates the snapshot for the given paths only, returning what changed under them.
+fn refresh(snapshot: &mut Snapshot, paths: &BTreeSet<PathBuf>) -> FileChanges {
+    let mut previous = Snapshot::new();
+    let mut current = Snapshot::new();
+
+    for path in paths {
+        // A removed or renamed directory only shows up as an event for the directory itself.
+        let under: Vec<PathBuf> = snapshot
+            .range(path.clone()..)
+            .take_while(|(file, _)| file.starts_with(path))
+            .map(|(file, _)| file.clone())
+            .collect();
+
+        for file in under {
+            if let Some(state) = snapshot.remove(&file) {
+                previous.insert(file, state);
+            }
+        }
+
+        if path.is_dir() {
+            current.append(&mut snapshot_directory(path));
+        } else if is_cast_file(path) {
+            record(&mut current, path);
+        }
+    }
+
+    let changes = compare(&previous, &current);
+
+    snapshot.append(&mut current);
+    changes
+}
+
+/// Records the state of every cast file under the given roots
+fn snapshot(roots: &[PathBuf]) -> Snapshot {
+    let mut snapshot = Snapshot::new();
+
+    for root in roots {
+        if root.is_dir() {
+            snapshot.append(&mut snapshot_directory(root));
+        } else {
+            record(&mut snapshot, root);
+        }
+    }
+
+    snapshot
+}
+
+/// Records the state of every cast file under the given directory
+fn snapshot_directory(directory: &Path) -> Snapshot {
+    let mut snapshot = Snapshot::new();
+
+    for entry in WalkDir::new(directory).into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+
+        if path.is_file() && is_cast_file(path) {
+            record(&mut snapshot, path);
+        }
+    }
+
+    snapshot
+}
+
+fn record(snapshot: &mut Snapshot, path: &Path) {
+    // Files that can't be read right now are treated as missing
+    if let Ok(metadata) = std::fs::metadata(path) {
+        snapshot.insert(
+            path.to_path_buf(),
+            (metadata.modified().ok(), metadata.len()),
+        );
+    }
+}
+
+fn compare(previous: &Snapshot, current: &Snapshot) -> FileChanges {
+    let mut changes = FileChanges::default();
+
+    for (path, state) in current {
+        match previous.get(path) {
+            None => changes.added.push(path.clone()),
+            Some(previous) if previous != state => changes.modified.push(path.clone()),
+            Some(_) => {}
+        }
+    }
+
+    changes.removed = previous
+        .keys()
+        .filter(|path| !current.contains_key(*path))
+        .cloned()
+        .collect();
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refreshes_only_the_changed_paths() {
+        let directory =
+            std::env::temp_dir().join(format!("cast_viewer_refresh_{}", std::process::id()));
+        let nested = directory.join("nested");
+
+        let _ = std::fs::remove_dir_all(&directory);
+        std::fs::create_dir_all(&nested).unwrap();
+
+        std::fs::write(directory.join("kept.cast"), b"kept").unwrap();
+        std::fs::write(nested.join("removed.cast"), b"removed").unwrap();
+
+        let mut snapshot = snapshot(std::slice::from_ref(&directory));
+
+        std::fs::remove_dir_all(&nested).unwrap();
+        std::fs::write(directory.join("added.cast"), b"added").unwrap();
+        std::fs::write(directory.join("ignored.txt"), b"ignored").unwrap();
+
+        let paths = BTreeSet::from([
+            nested.clone(),
+            directory.join("added.cast"),
+            directory.join("ignored.txt"),
+        ]);
+
+        let changes = refresh(&mut snapshot, &paths);
+
+        // The removed directory takes the files under it along.
+        assert_eq!(changes.added, vec![directory.join("added.cast")]);
+        assert_eq!(changes.removed, vec![nested.join("removed.cast")]);
+        assert!(changes.modified.is_empty());
+
+        assert_eq!(
+            snapshot.keys().cloned().collect::<Vec<_>>(),
+            vec![directory.join("added.cast"), directory.join("kept.cast")]
+        );
+
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+}
@@ -3,18 +3,38 @@
     windows_subsystem = "windows"
 )]
 mod asset_manager;
+mod cast_animation;
 mod cast_model;
+mod cast_scan;
+mod cast_validate;
+mod cast_write;
+mod cli;
+mod file_watcher;
+#[cfg(test)]
+mod test_cast;
+mod texture_export;
+mod texture_resolver;
+use porter_app::Sort;
 use porter_app::palette::*;
 
+/// Name of the app, also used for the saved settings file
+const APP_NAME: &str = "Cast Viewer";
+
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if let Some(code) = cli::run(APP_NAME, &args) {
+        std::process::exit(code);
+    }
+
     porter_app::initialize(asset_manager::AssetManager::new())
         .version(env!("CARGO_PKG_VERSION"))
-        .name("Cast Viewer")
+        .name(APP_NAME)
         .description("Cast Model Viewer")
-        .column("Name", 350, None, None)
-        .column("Type", 100, None, None)
-        .column("Status", 150, None, None)
-        .column("Info", 250, Some(TEXT_COLOR_SECONDARY), None)
-        .file_filter("Cast Models (*.cast)", vec!["cast"])
+        .column("Name", 350, None, Some(Sort::None))
+        .column("Type", 100, None, Some(Sort::None))
+        .column("Status", 150, None, Some(Sort::None))
+        .column("Info", 400, Some(TEXT_COLOR_SECONDARY), Some(Sort::None))
+        .file_filter("Cast Files (*.cast)", vec!["cast"])
         .run();
 }
@@ -0,0 +1,237 @@
+//! Builds small cast files byte by byte, for tests.
+
+/// A property with the given one or two character type, name, value count and little endian payload.
+pub fn property(property_type: &str, name: &str, array_length: u32, data: &[u8]) -> Vec<u8> {
+    let mut type_bytes = [0; 2];
+
+    type_bytes[..property_type.len()].copy_from_slice(property_type.as_bytes());
+
+    let mut buffer = Vec::new();
+
+    buffer.extend_from_slice(&type_bytes);
+    buffer.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    buffer.extend_from_slice(&array_length.to_le_bytes());
+    buffer.extend_from_slice(name.as_bytes());
+    buffer.extend_from_slice(data);
+    buffer
+}
+
+/// A string property.
+pub fn string(name: &str, value: &str) -> Vec<u8> {
+    let mut data = value.as_bytes().to_vec();
+
+    data.push(0);
+
+    property("s", name, 1, &data)
+}
+
+/// A 32 bit integer property.
+pub fn integers(name: &str, values: &[u32]) -> Vec<u8> {
+    let data: Vec<u8> = values
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect();
+
+    property("i", name, values.len() as u32, &data)
+}
+
+/// A 64 bit integer property, usually holding hashes.
+pub fn hashes(name: &str, values: &[u64]) -> Vec<u8> {
+    let data: Vec<u8> = values
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect();
+
+    property("l", name, values.len() as u32, &data)
+}
+
+/// A byte property.
+pub fn bytes(name: &str, values: &[u8]) -> Vec<u8> {
+    property("b", name, values.len() as u32, values)
+}
+
+/// A float property.
+pub fn floats(name: &str, values: &[f32]) -> Vec<u8> {
+    let data: Vec<u8> = values
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect();
+
+    property("f", name, values.len() as u32, &data)
+}
+
+/// A vector property, `v2`, `v3` or `v4` depending on the length of each vector.
+pub fn vectors<const N: usize>(name: &str, values: &[[f32; N]]) -> Vec<u8> {
+    let data: Vec<u8> = values
+        .iter()
+        .flatten()
+        .flat_map(|value| value.to_le_bytes())
+        .collect();
+
+    property(&format!("v{N}"), name, values.len() as u32, &data)
+}
+
+/// A node with the given four character identifier, hash, properties and children.
+pub fn node(identifier: &str, hash: u64, properties: &[Vec<u8>], children: &[Vec<u8>]) -> Vec<u8> {
+    let contents: Vec<u8> = properties
+        .iter()
+        .chain(children)
+        .flatten()
+        .copied()
+        .collect();
+
+    let mut buffer = Vec::new();
+
+    buffer.extend_from_slice(identifier.as_bytes());
+    buffer.extend_from_slice(&(24 + contents.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(&hash.to_le_bytes());
+    buffer.extend_from_slice(&(properties.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(&(children.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(&contents);
+    buffer
+}
+
+/// A whole cast file holding the given root nodes.
+pub fn file(roots: &[Vec<u8>]) -> Vec<u8> {
+    let mut buffer = Vec::new();
+
+    buffer.extend_from_slice(b"cast");
+    buffer.extend_from_slice(&1u32.to_le_bytes());
+    buffer.extend_from_slice(&(roots.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(&0u32.to_le_bytes());
+
+    for root in roots {
+        buffer.extend_from_slice(root);
+    }
+
+    buffer
+}
+
+/// A file holding a single model with the given name and children.
+pub fn model_file(name: &str, children: &[Vec<u8>]) -> Vec<u8> {
+    let model = node("modl", 0x1, &[string("n", name)], children);
+
+    file(&[node("root", 0, &[], &[model])])
+}
+
+/// A skeleton of two bones: a root, and a child one unit above it.
+pub fn two_bone_skeleton() -> Vec<u8> {
+    let bones = [
+        node(
+            "bone",
+            0x10,
+            &[
+                string("n", "root"),
+                integers("p", &[u32::MAX]),
+                vectors("lp", &[[0.0, 0.0, 0.0]]),
+                vectors("lr", &[[0.0, 0.0, 0.0, 1.0]]),
+                vectors("wp", &[[0.0, 0.0, 0.0]]),
+                vectors("wr", &[[0.0, 0.0, 0.0, 1.0]]),
+            ],
+            &[],
+        ),
+        node(
+            "bone",
+            0x11,
+            &[
+                string("n", "child"),
+                integers("p", &[0]),
+                vectors("lp", &[[0.0, 1.0, 0.0]]),
+                vectors("lr", &[[0.0, 0.0, 0.0, 1.0]]),
+                vectors("wp", &[[0.0, 1.0, 0.0]]),
+                vectors("wr", &[[0.0, 0.0, 0.0, 1.0]]),
+            ],
+            &[],
+        ),
+    ];
+
+    node("skel", 0x2, &[], &bones)
+}
+
+/// A file with one model: two bones, a material with a texture, and a triangle mesh weighted to both bones.
+pub fn triangle_model() -> Vec<u8> {
+    let material = node(
+        "matl",
+        0x30,
+        &[
+            string("n", "skin"),
+            string("t", "pbr"),
+            hashes("albedo", &[0x31]),
+        ],
+        &[node("file", 0x31, &[string("p", "skin_c.png")], &[])],
+    );
+
+    let mesh = node(
+        "mesh",
+        0x40,
+        &[
+            string("n", "body"),
+            vectors("vp", &[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]),
+            vectors("vn", &[[0.0, 0.0, 1.0], [0.0, 0.0, 1.0], [0.0, 0.0, 1.0]]),
+            bytes("ul", &[1]),
+            vectors("u0", &[[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]),
+            bytes("cl", &[1]),
+            integers("c0", &[0xFF00_00FF, 0xFF00_FF00, 0xFFFF_0000]),
+            bytes("mi", &[2]),
+            integers("wb", &[0, 1, 0, 1, 1, 0]),
+            floats("wv", &[1.0, 0.0, 0.5, 0.5, 1.0, 0.0]),
+            integers("f", &[0, 1, 2]),
+            hashes("m", &[0x30]),
+        ],
+        &[],
+    );
+
+    model_file("triangle", &[two_bone_skeleton(), material, mesh])
+}
+
+/// A file with metadata and one model: a triangle mesh with 16 bit faces, and a blend shape raising its top vertex.
+pub fn blend_shape_model() -> Vec<u8> {
+    let faces: Vec<u8> = [0u16, 1, 2]
+        .iter()
+        .flat_map(|index| index.to_le_bytes())
+        .collect();
+
+    let mesh = node(
+        "mesh",
+        0x40,
+        &[
+            string("n", "face"),
+            vectors("vp", &[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]),
+            property("h", "f", 3, &faces),
+        ],
+        &[],
+    );
+
+    let target = node(
+        "mesh",
+        0x41,
+        &[
+            string("n", "raise"),
+            vectors("vp", &[[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.5, 0.0]]),
+        ],
+        &[],
+    );
+
+    let blend_shape = node(
+        "blsh",
+        0x50,
+        &[
+            string("n", "shapes"),
+            hashes("b", &[0x40]),
+            hashes("t", &[0x41]),
+            floats("ts", &[0.5]),
+        ],
+        &[],
+    );
+
+    let model = node(
+        "modl",
+        0x1,
+        &[string("n", "blend")],
+        &[mesh, target, blend_shape],
+    );
+
+    let metadata = node("meta", 0x2, &[string("a", "author")], &[]);
+
+    file(&[node("root", 0, &[], &[model, metadata])])
+}
@@ -0,0 +1,154 @@
+use porter_app::{ImageNormalMapProcessing, Settings};
+use porter_model::{MaterialTextureRefUsage, Model};
+use porter_texture::{Image, ImageConvertOptions, ImageFileType, ImageFormat};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::texture_resolver::TextureResolver;
+
+/// Folder the textures of an exported model are written to, next to the model files
+pub const IMAGES_FOLDER: &str = "_images";
+
+/// Returns the file extension written for the given image file type
+pub fn image_file_extension(file_type: ImageFileType) -> &'static str {
+    match file_type {
+        ImageFileType::Dds => "dds",
+        ImageFileType::Png => "png",
+        ImageFileType::Tiff => "tiff",
+        ImageFileType::Tga => "tga",
+    }
+}
+
+/// Converts every texture the model references into the images folder of the output directory,
+/// and points the materials at the converted files.
+///
+/// Textures that can't be found are left as they are, so the exported model still names them.
+/// Returns the images written, or none when canceled, after removing the images written so far.
+pub fn export_model_images(
+    model: &mut Model,
+    resolver: &TextureResolver,
+    output_directory: &Path,
+    settings: &Settings,
+    cancel: &AtomicBool,
+) -> Result<Option<Vec<PathBuf>>, String> {
+    let images_directory = output_directory.join(IMAGES_FOLDER);
+    let extension = image_file_extension(settings.image_file_type());
+
+    // The same texture may be used by several materials, or several slots, and is only written once.
+    let mut exported: HashMap<String, Option<String>> = HashMap::new();
+    let mut used_names: HashSet<String> = HashSet::new();
+    let mut written: Vec<PathBuf> = Vec::new();
+
+    for material in &mut model.materials {
+        for texture in &mut material.textures {
+            if let Some(file_name) = exported.get(&texture.file_name) {
+                if let Some(file_name) = file_name {
+                    texture.file_name = file_name.clone();
+                }
+                continue;
+            }
+
+            if cancel.load(Ordering::Relaxed) {
+                remove_images(output_directory, &written);
+                return Ok(None);
+            }
+
+            let (path, image_file_type) = match resolver.resolve(&texture.file_name) {
+                Ok(resolved) => resolved,
+                Err(e) => {
+                    eprintln!("Material {}: {e}", material.name);
+                    exported.insert(texture.file_name.clone(), None);
+                    continue;
+                }
+            };
+
+            let name = unique_name(&path, &mut used_names);
+            let output = images_directory.join(format!("{name}.{extension}"));
+
+            if written.is_empty() {
+                std::fs::create_dir_all(&images_directory)
+                    .map_err(|e| format!("Could not create images directory: {e}"))?;
+            }
+
+            if let Err(e) = save_texture(
+                &path,
+                image_file_type,
+                texture.texture_usage,
+                &output,
+                settings,
+            ) {
+                remove_images(output_directory, &written);
+                return Err(e);
+            }
+
+            written.push(output);
+
+            let file_name = format!("{IMAGES_FOLDER}/{name}.{extension}");
+
+            exported.insert(texture.file_name.clone(), Some(file_name.clone()));
+
+            texture.file_name = file_name;
+        }
+    }
+
+    Ok(Some(written))
+}
+
+/// Loads the given texture, reprocesses it when it's a normal map, and saves it in the image file type of the settings.
+pub fn save_texture(
+    path: &Path,
+    image_file_type: ImageFileType,
+    usage: MaterialTextureRefUsage,
+    output: &Path,
+    settings: &Settings,
+) -> Result<(), String> {
+    let mut image = Image::load(path, image_file_type)
+        .map_err(|e| format!("Failed to load image {}: {e:?}", path.display()))?;
+
+    if usage == MaterialTextureRefUsage::Normal {
+        let options = match settings.image_normal_map_processing() {
+            ImageNormalMapProcessing::None => ImageConvertOptions::None,
+            ImageNormalMapProcessing::OpenGl => ImageConvertOptions::ReconstructZ,
+            ImageNormalMapProcessing::DirectX => ImageConvertOptions::ReconstructZInvertY,
+        };
+
+        image
+            .convert(ImageFormat::R8G8B8A8Unorm, options)
+            .map_err(|e| format!("Failed to convert normal map {}: {e:?}", path.display()))?;
+    }
+
+    image
+        .save(output, settings.image_file_type())
+        .map_err(|e| format!("Failed to export image {}: {e:?}", output.display()))
+}
+
+/// The file stem of the texture, numbered when another texture already took it.
+fn unique_name(path: &Path, used_names: &mut HashSet<String>) -> String {
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("texture")
+        .to_string();
+
+    let mut name = stem.clone();
+    let mut index = 1;
+
+    // File systems may ignore case, so names are compared without it.
+    while !used_names.insert(name.to_lowercase()) {
+        name = format!("{stem}_{index}");
+        index += 1;
+    }
+
+    name
+}
+
+/// Deletes the given images written by `export_model_images`, and the images folder if nothing else is left in it.
+pub fn remove_images(output_directory: &Path, written: &[PathBuf]) {
+    for file in written {
+        let _ = std::fs::remove_file(file);
+    }
+
+    // Only succeeds when the directory is empty
+    let _ = std::fs::remove_dir(output_directory.join(IMAGES_FOLDER));
+}
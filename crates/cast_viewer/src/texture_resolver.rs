@@ -0,0 +1,185 @@
+use porter_texture::ImageFileType;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// Extensions tried, in order, when a texture isn't found under its own name
+const ALTERNATE_EXTENSIONS: [&str; 10] = [
+    "dds", "png", "tga", "tif", "tiff", "jpg", "jpeg", "bmp", "exr", "hdr",
+];
+
+/// Footer at the end of newer tga files, the only thing identifying them
+const TGA_SIGNATURE: &[u8] = b"TRUEVISION-XFILE.\0";
+
+/// Finds texture files referenced by a cast file, and works out their format.
+#[derive(Debug)]
+pub struct TextureResolver {
+    directories: Vec<PathBuf>,
+}
+
+impl TextureResolver {
+    /// Searches next to the given cast file first, then the given texture directories in order.
+    pub fn new(file_name: &Path, texture_directories: &[PathBuf]) -> Self {
+        let mut directories = vec![file_name.parent().unwrap_or(Path::new(".")).to_path_buf()];
+
+        directories.extend(texture_directories.iter().cloned());
+
+        Self { directories }
+    }
+
+    /// Finds the given texture, returning its path and format, or why it can't be used.
+    pub fn resolve(&self, texture: &str) -> Result<(PathBuf, ImageFileType), String> {
+        if texture.is_empty() {
+            return Err("texture has no file name".to_string());
+        }
+
+        let path = self.find(Path::new(texture)).ok_or_else(|| {
+            let searched: Vec<_> = self
+                .directories
+                .iter()
+                .map(|directory| directory.display().to_string())
+                .collect();
+
+            format!("{texture} not found in {}", searched.join(", "))
+        })?;
+
+        let file_type = detect_file_type(&path)?;
+
+        Ok((path, file_type))
+    }
+
+    fn find(&self, texture: &Path) -> Option<PathBuf> {
+        if texture.is_absolute() && texture.is_file() {
+            return Some(texture.to_path_buf());
+        }
+
+        let file_name = texture.file_name()?;
+
+        // Relative paths are tried as given, then by file name alone, in every directory.
+        let candidates: Vec<PathBuf> = self
+            .directories
+            .iter()
+            .flat_map(|directory| {
+                let mut candidates = Vec::with_capacity(2);
+
+                if !texture.is_absolute() {
+                    candidates.push(directory.join(texture));
+                }
+
+                candidates.push(directory.join(file_name));
+                candidates
+            })
+            .collect();
+
+        candidates
+            .iter()
+            .find_map(|candidate| find_case_insensitive(candidate))
+            .or_else(|| {
+                candidates
+                    .iter()
+                    .find_map(|candidate| find_alternate_extension(candidate))
+            })
+    }
+}
+
+/// Finds the given file, ignoring the case of its file name.
+fn find_case_insensitive(path: &Path) -> Option<PathBuf> {
+    if path.is_file() {
+        return Some(path.to_path_buf());
+    }
+
+    let file_name = path.file_name()?.to_str()?;
+
+    std::fs::read_dir(path.parent()?)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|candidate| {
+            candidate.is_file()
+                && candidate
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.eq_ignore_ascii_case(file_name))
+        })
+}
+
+/// Finds a file with the same name as the given one, but another image extension.
+fn find_alternate_extension(path: &Path) -> Option<PathBuf> {
+    let stem = path.file_stem()?.to_str()?;
+
+    let mut matches: Vec<(usize, PathBuf)> = std::fs::read_dir(path.parent()?)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|candidate| {
+            candidate
+                .file_stem()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.eq_ignore_ascii_case(stem))
+        })
+        .filter_map(|candidate| {
+            let extension = candidate.extension()?.to_str()?.to_ascii_lowercase();
+            let priority = ALTERNATE_EXTENSIONS
+                .iter()
+                .position(|alternate| *alternate == extension)?;
+
+            Some((priority, candidate))
+        })
+        .collect();
+
+    matches.sort();
+    matches.into_iter().next().map(|(_, candidate)| candidate)
+}
+
+/// Works out the format of an image from its contents, falling back to the extension for formats without a magic.
+fn detect_file_type(path: &Path) -> Result<ImageFileType, String> {
+    let mut file = File::open(path).map_err(|e| format!("{}: {e}", path.display()))?;
+
+    let mut magic = [0; 12];
+    let read = file
+        .read(&mut magic)
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+    let magic = &magic[..read];
+
+    let unsupported = |format: &str| {
+        Err(format!(
+            "{}: {format} images can't be previewed",
+            path.display()
+        ))
+    };
+
+    if magic.starts_with(b"DDS ") {
+        return Ok(ImageFileType::Dds);
+    } else if magic.starts_with(b"\x89PNG\r\n\x1A\n") {
+        return Ok(ImageFileType::Png);
+    } else if magic.starts_with(b"II*\0") || magic.starts_with(b"MM\0*") {
+        return Ok(ImageFileType::Tiff);
+    } else if magic.starts_with(b"\xFF\xD8\xFF") {
+        return unsupported("jpg");
+    } else if magic.starts_with(b"BM") {
+        return unsupported("bmp");
+    } else if magic.starts_with(b"\x76\x2F\x31\x01") {
+        return unsupported("exr");
+    } else if magic.starts_with(b"#?RADIANCE") || magic.starts_with(b"#?RGBE") {
+        return unsupported("hdr");
+    }
+
+    let mut footer = [0; TGA_SIGNATURE.len()];
+
+    let has_tga_footer = file
+        .seek(SeekFrom::End(-(TGA_SIGNATURE.len() as i64)))
+        .and_then(|_| file.read_exact(&mut footer))
+        .is_ok_and(|_| footer == TGA_SIGNATURE);
+
+    let tga_extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("tga"));
+
+    // Older tga files have no footer, so their extension is all there is to go on.
+    if has_tga_footer || tga_extension {
+        Ok(ImageFileType::Tga)
+    } else {
+        Err(format!("{}: unknown image format", path.display()))
+    }
+}
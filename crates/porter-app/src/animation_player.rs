@@ -0,0 +1,470 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use porter_animation::Animation;
+use porter_animation::CurveAttribute;
+use porter_animation::Keyframe;
+use porter_animation::KeyframeValue;
+
+use porter_math::Quaternion;
+use porter_math::Vector3;
+
+use porter_model::Model;
+
+use porter_texture::Image;
+
+use crate::apply_blend_shapes;
+
+/// A bone transform split into its components.
+#[derive(Debug, Clone, Copy)]
+struct Transform {
+    position: Vector3,
+    rotation: Quaternion,
+    scale: Vector3,
+}
+
+/// Plays an animation on a model skeleton, producing posed copies of the model.
+pub struct AnimationPlayer {
+    name: String,
+    model: Model,
+    images: Arc<[Option<Image>]>,
+    animation: Animation,
+    bind_pose: Vec<Transform>,
+    bone_curves: Vec<Vec<usize>>,
+    missing_bones: Vec<String>,
+    frame: f32,
+    frame_count: f32,
+    playing: bool,
+    looping: bool,
+    last_tick: Option<Instant>,
+}
+
+impl AnimationPlayer {
+    /// Constructs a new player for the given model and animation, starting paused on the first frame.
+    pub fn new(
+        name: String,
+        model: Model,
+        images: Arc<[Option<Image>]>,
+        animation: Animation,
+    ) -> Self {
+        let bone_indices: HashMap<&str, usize> = model
+            .skeleton
+            .bones
+            .iter()
+            .enumerate()
+            .filter_map(|(index, bone)| bone.name.as_deref().map(|name| (name, index)))
+            .collect();
+
+        let mut bone_curves = vec![Vec::new(); model.skeleton.bones.len()];
+
+        for (curve_index, curve) in animation.curves.iter().enumerate() {
+            if let Some(bone_index) = bone_indices.get(curve.name()) {
+                bone_curves[*bone_index].push(curve_index);
+            }
+        }
+
+        let missing_bones = model
+            .skeleton
+            .bones
+            .iter()
+            .zip(&bone_curves)
+            .filter(|(_, curves)| curves.is_empty())
+            .map(|(bone, _)| {
+                bone.name
+                    .clone()
+                    .unwrap_or_else(|| String::from("<unnamed>"))
+            })
+            .collect();
+
+        let bind_pose = model
+            .skeleton
+            .bones
+            .iter()
+            .map(|bone| Transform {
+                position: bone.world_position,
+                rotation: bone.world_rotation,
+                scale: bone.world_scale,
+            })
+            .collect();
+
+        let frame_count = animation
+            .curves
+            .iter()
+            .flat_map(|curve| curve.keyframes().iter().map(|keyframe| keyframe.frame))
+            .max()
+            .unwrap_or_default()
+            .max(1) as f32;
+
+        let looping = animation.looping;
+
+        Self {
+            name,
+            model,
+            images,
+            animation,
+            bind_pose,
+            bone_curves,
+            missing_bones,
+            frame: 0.0,
+            frame_count,
+            playing: false,
+            looping,
+            last_tick: None,
+        }
+    }
+
+    /// The name of the previewed asset.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The images used by the model materials, shared with every posed copy of the model.
+    pub fn images(&self) -> &Arc<[Option<Image>]> {
+        &self.images
+    }
+
+    /// Bones in the skeleton that no curve in the animation drives.
+    pub fn missing_bones(&self) -> &[String] {
+        &self.missing_bones
+    }
+
+    /// The current frame of the animation.
+    pub fn frame(&self) -> f32 {
+        self.frame
+    }
+
+    /// The last frame of the animation.
+    pub fn frame_count(&self) -> f32 {
+        self.frame_count
+    }
+
+    /// Whether or not the animation is playing.
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Whether or not the animation loops once it reaches the last frame.
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    /// Starts playing the animation, restarting it if it already finished.
+    pub fn play(&mut self) {
+        if self.frame >= self.frame_count {
+            self.frame = 0.0;
+        }
+
+        self.playing = true;
+        self.last_tick = None;
+    }
+
+    /// Pauses the animation on the current frame.
+    pub fn pause(&mut self) {
+        self.playing = false;
+        self.last_tick = None;
+    }
+
+    /// Sets whether or not the animation loops.
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Moves the animation to the given frame.
+    pub fn scrub(&mut self, frame: f32) {
+        self.frame = frame.clamp(0.0, self.frame_count);
+    }
+
+    /// Advances the animation to the given time, returns true if the frame changed.
+    pub fn tick(&mut self, now: Instant) -> bool {
+        if !self.playing {
+            return false;
+        }
+
+        let Some(last_tick) = self.last_tick.replace(now) else {
+            return false;
+        };
+
+        let elapsed = now.saturating_duration_since(last_tick).as_secs_f32();
+
+        self.frame += elapsed * self.animation.framerate;
+
+        if self.frame >= self.frame_count {
+            if self.looping {
+                self.frame %= self.frame_count;
+            } else {
+                self.frame = self.frame_count;
+                self.playing = false;
+            }
+        }
+
+        true
+    }
+
+    /// The model being animated, in its bind pose.
+    pub fn model(&self) -> &Model {
+        &self.model
+    }
+
+    /// Produces a copy of the model posed at the current frame, with the given blend shape weights.
+    pub fn pose(&self, blend_weights: &[f32]) -> Model {
+        let mut model = self.model.clone();
+
+        // Shapes deform the bind pose, before the skeleton moves it.
+        apply_blend_shapes(&mut model, blend_weights);
+
+        let mut posed: Vec<Transform> = Vec::with_capacity(model.skeleton.bones.len());
+        let mut local_scales: Vec<Vector3> = Vec::with_capacity(model.skeleton.bones.len());
+
+        for (bone_index, bone) in model.skeleton.bones.iter_mut().enumerate() {
+            let mut local = Transform {
+                position: bone.local_position,
+                rotation: bone.local_rotation,
+                scale: bone.local_scale,
+            };
+
+            for curve_index in &self.bone_curves[bone_index] {
+                let curve = &self.animation.curves[*curve_index];
+
+                let Some(value) = sample(curve.keyframes(), self.frame) else {
+                    continue;
+                };
+
+                match (curve.attribute(), value) {
+                    (CurveAttribute::Rotation, KeyframeValue::Quaternion(rotation)) => {
+                        local.rotation = rotation;
+                    }
+                    (CurveAttribute::TranslateX, KeyframeValue::Float(value)) => {
+                        local.position.x = value;
+                    }
+                    (CurveAttribute::TranslateY, KeyframeValue::Float(value)) => {
+                        local.position.y = value;
+                    }
+                    (CurveAttribute::TranslateZ, KeyframeValue::Float(value)) => {
+                        local.position.z = value;
+                    }
+                    (CurveAttribute::ScaleX, KeyframeValue::Float(value)) => {
+                        local.scale.x = value;
+                    }
+                    (CurveAttribute::ScaleY, KeyframeValue::Float(value)) => {
+                        local.scale.y = value;
+                    }
+                    (CurveAttribute::ScaleZ, KeyframeValue::Float(value)) => {
+                        local.scale.z = value;
+                    }
+                    _ => {
+                        // Visibility and mismatched curves don't affect the pose.
+                    }
+                }
+            }
+
+            // Bones are stored parent first, anything else is treated as a root.
+            let world = match usize::try_from(bone.parent) {
+                Ok(parent) if parent < bone_index => {
+                    let parent_local_scale = local_scales[parent];
+                    let parent = posed[parent];
+
+                    // Segment scale compensation only undoes the parent's own scale, like in Maya.
+                    let scale = if bone.segment_scale_compensate {
+                        divide(multiply(parent.scale, local.scale), parent_local_scale)
+                    } else {
+                        multiply(parent.scale, local.scale)
+                    };
+
+                    Transform {
+                        position: add(
+                            parent.position,
+                            rotate(parent.rotation, multiply(local.position, parent.scale)),
+                        ),
+                        rotation: normalize(quaternion_multiply(parent.rotation, local.rotation)),
+                        scale,
+                    }
+                }
+                _ => local,
+            };
+
+            bone.local_position = local.position;
+            bone.local_rotation = local.rotation;
+            bone.local_scale = local.scale;
+            bone.world_position = world.position;
+            bone.world_rotation = world.rotation;
+            bone.world_scale = world.scale;
+
+            posed.push(world);
+            local_scales.push(local.scale);
+        }
+
+        for mesh in &mut model.meshes {
+            let maximum_influence = mesh.vertices.maximum_influence();
+
+            if maximum_influence == 0 {
+                continue;
+            }
+
+            for vertex_index in 0..mesh.vertices.len() {
+                let vertex = mesh.vertices.vertex(vertex_index);
+
+                let position = vertex.position();
+                let normal = vertex.normal();
+
+                let mut skinned_position = Vector3::new(0.0, 0.0, 0.0);
+                let mut skinned_normal = Vector3::new(0.0, 0.0, 0.0);
+                let mut total = 0.0;
+
+                for weight_index in 0..maximum_influence {
+                    let weight = vertex.weight(weight_index);
+                    let bone = weight.bone as usize;
+
+                    if weight.value <= 0.0 || bone >= posed.len() {
+                        continue;
+                    }
+
+                    let bind = self.bind_pose[bone];
+                    let pose = posed[bone];
+
+                    let local_position = divide(
+                        rotate(conjugate(bind.rotation), subtract(position, bind.position)),
+                        bind.scale,
+                    );
+                    let local_normal = rotate(conjugate(bind.rotation), normal);
+
+                    let world_position = add(
+                        rotate(pose.rotation, multiply(local_position, pose.scale)),
+                        pose.position,
+                    );
+                    let world_normal = rotate(pose.rotation, local_normal);
+
+                    skinned_position = add(skinned_position, scale(world_position, weight.value));
+                    skinned_normal = add(skinned_normal, scale(world_normal, weight.value));
+                    total += weight.value;
+                }
+
+                if total <= 0.0 {
+                    continue;
+                }
+
+                let mut vertex = mesh.vertices.vertex_mut(vertex_index);
+
+                vertex.set_position(scale(skinned_position, 1.0 / total));
+                vertex.set_normal(normalize_vector(skinned_normal));
+            }
+        }
+
+        model
+    }
+}
+
+/// Samples the keyframes at the given frame, interpolating between the surrounding keys.
+fn sample(keyframes: &[Keyframe], frame: f32) -> Option<KeyframeValue> {
+    let next = keyframes.partition_point(|keyframe| (keyframe.frame as f32) <= frame);
+
+    // Frames before the first key hold the first value.
+    let Some(previous) = next.checked_sub(1).and_then(|index| keyframes.get(index)) else {
+        return keyframes.first().map(|keyframe| keyframe.value.clone());
+    };
+
+    let Some(next) = keyframes.get(next) else {
+        return Some(previous.value.clone());
+    };
+
+    let span = (next.frame - previous.frame).max(1) as f32;
+    let t = ((frame - previous.frame as f32) / span).clamp(0.0, 1.0);
+
+    let value = match (&previous.value, &next.value) {
+        (KeyframeValue::Float(a), KeyframeValue::Float(b)) => KeyframeValue::Float(a + (b - a) * t),
+        (KeyframeValue::Quaternion(a), KeyframeValue::Quaternion(b)) => {
+            KeyframeValue::Quaternion(nlerp(*a, *b, t))
+        }
+        (value, _) => value.clone(),
+    };
+
+    Some(value)
+}
+
+/// Normalized linear interpolation between two rotations, along the shortest path.
+fn nlerp(a: Quaternion, b: Quaternion, t: f32) -> Quaternion {
+    let dot = a.x * b.x + a.y * b.y + a.z * b.z + a.w * b.w;
+    let sign = if dot < 0.0 { -1.0 } else { 1.0 };
+
+    normalize(Quaternion::new(
+        a.x + (b.x * sign - a.x) * t,
+        a.y + (b.y * sign - a.y) * t,
+        a.z + (b.z * sign - a.z) * t,
+        a.w + (b.w * sign - a.w) * t,
+    ))
+}
+
+/// Normalizes a rotation, leaving degenerate rotations as identity.
+fn normalize(q: Quaternion) -> Quaternion {
+    let length = (q.x * q.x + q.y * q.y + q.z * q.z + q.w * q.w).sqrt();
+
+    if length <= f32::EPSILON {
+        return Quaternion::new(0.0, 0.0, 0.0, 1.0);
+    }
+
+    Quaternion::new(q.x / length, q.y / length, q.z / length, q.w / length)
+}
+
+/// Combines two rotations, applying `b` first and then `a`.
+fn quaternion_multiply(a: Quaternion, b: Quaternion) -> Quaternion {
+    Quaternion::new(
+        a.w * b.x + a.x * b.w + a.y * b.z - a.z * b.y,
+        a.w * b.y - a.x * b.z + a.y * b.w + a.z * b.x,
+        a.w * b.z + a.x * b.y - a.y * b.x + a.z * b.w,
+        a.w * b.w - a.x * b.x - a.y * b.y - a.z * b.z,
+    )
+}
+
+/// The inverse of a unit rotation.
+fn conjugate(q: Quaternion) -> Quaternion {
+    Quaternion::new(-q.x, -q.y, -q.z, q.w)
+}
+
+/// Rotates a vector by a unit rotation.
+fn rotate(q: Quaternion, v: Vector3) -> Vector3 {
+    let u = Vector3::new(q.x, q.y, q.z);
+    let t = scale(cross(u, v), 2.0);
+
+    add(add(v, scale(t, q.w)), cross(u, t))
+}
+
+fn cross(a: Vector3, b: Vector3) -> Vector3 {
+    Vector3::new(
+        a.y * b.z - a.z * b.y,
+        a.z * b.x - a.x * b.z,
+        a.x * b.y - a.y * b.x,
+    )
+}
+
+fn add(a: Vector3, b: Vector3) -> Vector3 {
+    Vector3::new(a.x + b.x, a.y + b.y, a.z + b.z)
+}
+
+fn subtract(a: Vector3, b: Vector3) -> Vector3 {
+    Vector3::new(a.x - b.x, a.y - b.y, a.z - b.z)
+}
+
+fn multiply(a: Vector3, b: Vector3) -> Vector3 {
+    Vector3::new(a.x * b.x, a.y * b.y, a.z * b.z)
+}
+
+/// Component-wise division, ignoring zero scale components.
+fn divide(a: Vector3, b: Vector3) -> Vector3 {
+    let divide = |a: f32, b: f32| if b.abs() <= f32::EPSILON { a } else { a / b };
+
+    Vector3::new(divide(a.x, b.x), divide(a.y, b.y), divide(a.z, b.z))
+}
+
+fn scale(v: Vector3, factor: f32) -> Vector3 {
+    Vector3::new(v.x * factor, v.y * factor, v.z * factor)
+}
+
+fn normalize_vector(v: Vector3) -> Vector3 {
+    let length = (v.x * v.x + v.y * v.y + v.z * v.z).sqrt();
+
+    if length <= f32::EPSILON {
+        return v;
+    }
+
+    scale(v, 1.0 / length)
+}
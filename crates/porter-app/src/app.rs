@@ -95,8 +95,12 @@ impl App {
             PreviewWindowClosed => self.on_preview_window_closed(),
             PreviewToggle => self.on_preview_toggle(),
             PreviewRequest => self.on_preview_request(),
+            PreviewAnimationRequest(file) => self.on_preview_animation_request(file),
             LoadUpdate(result) => self.on_load_update(result),
             ProgressUpdate(finished, progress) => self.on_progress_update(finished, progress),
+            AssetsChanged(removed, moved, preview_changed) => {
+                self.on_assets_changed(removed, moved, preview_changed)
+            }
             PreviewUpdate(request_id, asset) => self.on_preview_update(request_id, asset),
             ExportSelected => self.on_export_selected(),
             ExportAll => self.on_export_all(),
@@ -173,7 +177,14 @@ impl App {
             })
         });
 
-        Subscription::batch([events, controller])
+        // Only redraw every frame while an animation is playing in the preview, or a new pose waits to be shown.
+        let animation = if self.state.animation_playing || self.state.pose_pending {
+            window::frames().map(|now| Message::from(PreviewMessage::AnimationTick(now)))
+        } else {
+            Subscription::none()
+        };
+
+        Subscription::batch([events, controller, animation])
     }
 
     /// Handles rendering a given window.
@@ -287,6 +298,38 @@ impl App {
             return self.on_check_reload();
         } else {
             self.state.progress = progress.clamp(0, 100);
+
+            // Loads publish assets in batches, so newly found assets need to become visible.
+            if self.state.loading {
+                self.state.extend_item_range();
+            }
+        }
+
+        Task::none()
+    }
+
+    /// Occurs when watched files changed on disk, and the asset manager has updated its assets.
+    fn on_assets_changed(
+        &mut self,
+        removed: Vec<PathBuf>,
+        moved: Vec<Option<usize>>,
+        preview_changed: bool,
+    ) -> Task<Message> {
+        if let Some(last_load) = &mut self.state.last_load {
+            last_load.retain(|file| !removed.contains(file));
+        }
+
+        // Selected rows follow their assets, and are dropped along with removed assets.
+        self.state.assets_selected = self
+            .state
+            .assets_selected
+            .iter()
+            .filter_map(|index| moved.get(*index).copied().flatten())
+            .collect();
+        self.state.refresh_item_range();
+
+        if preview_changed && !self.state.assets_selected.is_empty() {
+            return Task::done(Message::from(PreviewMessage::Request));
         }
 
         Task::none()
@@ -309,10 +352,15 @@ impl App {
     fn on_load_update(&mut self, result: Result<(), String>) -> Task<Message> {
         self.state.loading = false;
         self.state.progress = 0;
+        self.state.export_canceled = false;
         self.state.reset_item_range();
 
         let icon = self.state.asset_manager.display_icon();
 
+        self.state
+            .asset_manager
+            .watch(self.state.settings.clone(), self.state.controller.clone());
+
         if let Err(e) = result {
             self.state.last_load = None;
 
@@ -361,6 +409,8 @@ impl App {
     /// Occurs when the user closes the preview window.
     fn on_preview_window_closed(&mut self) -> Task<Message> {
         self.preview_window = None;
+        self.state.animation_playing = false;
+        self.state.pose_pending = false;
 
         Task::none()
     }
@@ -408,6 +458,34 @@ impl App {
         Task::none()
     }
 
+    /// Occurs when we need to request the selected model be previewed with an animation.
+    fn on_preview_animation_request(&mut self, file: PathBuf) -> Task<Message> {
+        let Some(index) = self.state.assets_selected.first().cloned() else {
+            return Task::none();
+        };
+
+        if self.state.asset_manager.assets_empty() {
+            return Task::none();
+        }
+
+        let manager = self.state.asset_manager.clone();
+        let controller = self.state.controller.clone();
+        let settings = self.state.settings.clone();
+        let request_id = self
+            .state
+            .asset_preview_id
+            .map(|id| id + 1)
+            .unwrap_or_default();
+
+        self.state.asset_preview_id = Some(request_id);
+
+        porter_threads::spawn(move || {
+            manager.preview_animation(settings, index, file, request_id, controller);
+        });
+
+        Task::none()
+    }
+
     /// Occurs when the user requests to export selected assets.
     fn on_export_selected(&mut self) -> Task<Message> {
         if self.state.is_busy() {
@@ -452,7 +530,7 @@ impl App {
         Task::none()
     }
 
-    /// Occurs when the user requests to cancel an export.
+    /// Occurs when the user requests to cancel an export or load.
     fn on_export_cancel(&mut self) -> Task<Message> {
         if self.state.export_canceled {
             return Task::none();
@@ -502,7 +580,9 @@ impl App {
         self.state.assets_selected.clear();
 
         porter_threads::spawn(move || {
-            controller.load_update(manager.load_directory(settings, files));
+            let result = manager.load_directory(settings, files, controller.clone());
+
+            controller.load_update(result);
         });
 
         Task::none()
@@ -50,6 +50,8 @@ pub struct AppState {
     pub(crate) asset_manager: Arc<dyn AssetManager + 'static>,
     pub(crate) asset_columns: Vec<AssetColumn>,
     pub(crate) asset_preview_id: Option<u64>,
+    pub(crate) animation_playing: bool,
+    pub(crate) pose_pending: bool,
     pub(crate) assets_selected: BTreeSet<usize>,
 }
 
@@ -75,6 +77,8 @@ impl AppState {
             asset_manager: Arc::new(asset_manager),
             asset_columns: Vec::new(),
             asset_preview_id: None,
+            animation_playing: false,
+            pose_pending: false,
             assets_selected: BTreeSet::new(),
         }
     }
@@ -84,6 +88,22 @@ impl AppState {
         self.loading || self.exporting
     }
 
+    /// Grows the virtual list item range to cover newly loaded assets, without moving it.
+    pub(crate) fn extend_item_range(&mut self) {
+        let end = (self.item_range.start + 50).min(self.asset_manager.assets_visible());
+
+        self.item_range = self.item_range.start..end.max(self.item_range.end);
+    }
+
+    /// Shrinks the virtual list item range to the visible assets, then grows it to cover new ones.
+    pub(crate) fn refresh_item_range(&mut self) {
+        let end = self.item_range.end.min(self.asset_manager.assets_visible());
+
+        self.item_range = self.item_range.start.min(end)..end;
+
+        self.extend_item_range();
+    }
+
     /// Resets the virtual list item range.
     pub(crate) fn reset_item_range(&mut self) {
         self.item_range = 0..50.min(self.asset_manager.assets_visible())
@@ -0,0 +1,87 @@
+use std::fmt;
+
+/// How serious an issue found in an asset is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AssetIssueSeverity {
+    /// The data is suspicious, but can still be used.
+    Warning,
+    /// The data is broken, and was skipped or repaired while loading.
+    Error,
+}
+
+/// A problem found in the data of an asset.
+#[derive(Debug, Clone)]
+pub struct AssetIssue {
+    /// How serious the issue is.
+    pub severity: AssetIssueSeverity,
+    /// Path to the node the issue was found in.
+    pub path: String,
+    /// The property the issue was found in, if any.
+    pub property: Option<String>,
+    /// A description of the issue.
+    pub message: String,
+}
+
+impl AssetIssue {
+    /// Constructs a new error issue.
+    pub fn error<P: Into<String>, M: Into<String>>(
+        path: P,
+        property: Option<&str>,
+        message: M,
+    ) -> Self {
+        Self {
+            severity: AssetIssueSeverity::Error,
+            path: path.into(),
+            property: property.map(String::from),
+            message: message.into(),
+        }
+    }
+
+    /// Constructs a new warning issue.
+    pub fn warning<P: Into<String>, M: Into<String>>(
+        path: P,
+        property: Option<&str>,
+        message: M,
+    ) -> Self {
+        Self {
+            severity: AssetIssueSeverity::Warning,
+            path: path.into(),
+            property: property.map(String::from),
+            message: message.into(),
+        }
+    }
+
+    /// Whether or not this issue is an error.
+    pub fn is_error(&self) -> bool {
+        self.severity == AssetIssueSeverity::Error
+    }
+
+    /// The node path, followed by the property when there is one.
+    pub fn location(&self) -> String {
+        match &self.property {
+            Some(property) => format!("{}.{}", self.path, property),
+            None => self.path.clone(),
+        }
+    }
+}
+
+impl fmt::Display for AssetIssueSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Warning => write!(f, "Warning"),
+            Self::Error => write!(f, "Error"),
+        }
+    }
+}
+
+impl fmt::Display for AssetIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}: {}",
+            self.severity,
+            self.location(),
+            self.message
+        )
+    }
+}
@@ -25,6 +25,16 @@ pub trait AssetManager: Send + Sync + 'static {
         false
     }
 
+    /// Whether or not the asset manager supports watching loaded files for changes.
+    fn supports_watching(&self) -> bool {
+        false
+    }
+
+    /// Whether or not the asset manager searches extra texture directories.
+    fn supports_texture_directories(&self) -> bool {
+        false
+    }
+
     /// Gets information about the specific asset, in the form of column data.
     fn assets_info(&self, index: usize) -> Vec<(String, Option<Color>)>;
 
@@ -53,8 +63,21 @@ pub trait AssetManager: Send + Sync + 'static {
     /// Loads one or more given files.
     fn load_files(&self, settings: Settings, files: Vec<PathBuf>) -> Result<(), String>;
 
-    /// Loads a directory.
-    fn load_directory(&self, settings: Settings, files: PathBuf) -> Result<(), String>;
+    /// Loads a directory, reporting progress and publishing assets as they are found.
+    fn load_directory(
+        &self,
+        settings: Settings,
+        files: PathBuf,
+        controller: Controller,
+    ) -> Result<(), String>;
+
+    /// Starts watching the loaded files for changes when enabled in the settings, otherwise stops watching.
+    ///
+    /// Changes are reported back through `Controller::assets_changed`.
+    fn watch(&self, settings: Settings, controller: Controller) {
+        let _ = settings;
+        let _ = controller;
+    }
 
     /// Loads a running game instance.
     fn load_game(&self, settings: Settings) -> Result<(), String>;
@@ -71,7 +94,7 @@ pub trait AssetManager: Send + Sync + 'static {
     /// Request one or more assets be exported.
     fn export(&self, settings: Settings, assets: Vec<usize>, controller: Controller);
 
-    /// Cancels an active export.
+    /// Cancels an active export or directory load.
     fn export_cancel(&self);
 
     /// Request the given assets data for preview, optionally forcing a raw file preview.
@@ -83,4 +106,18 @@ pub trait AssetManager: Send + Sync + 'static {
         request_id: u64,
         controller: Controller,
     );
+
+    /// Request the given model asset be previewed with the animation file applied to its skeleton.
+    fn preview_animation(
+        &self,
+        settings: Settings,
+        asset: usize,
+        animation: PathBuf,
+        request_id: u64,
+        controller: Controller,
+    ) {
+        let _ = animation;
+
+        self.preview(settings, asset, false, request_id, controller);
+    }
 }
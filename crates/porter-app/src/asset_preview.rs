@@ -1,8 +1,13 @@
+use porter_animation::Animation;
+
 use porter_model::MaterialTextureRefUsage;
 use porter_model::Model;
 
 use porter_texture::Image;
 
+use crate::AssetIssue;
+use crate::AssetTreeNode;
+
 /// The result of an assets data to be previewed.
 #[derive(Debug, Clone)]
 pub enum AssetPreview {
@@ -16,6 +21,14 @@ pub enum AssetPreview {
     Image(String, Image),
     /// A model asset for preview.
     Model(String, Model, Vec<Option<Image>>),
+    /// A model asset for preview, posed by the given animation.
+    AnimatedModel(String, Model, Vec<Option<Image>>, Animation),
     /// A material asset for preview.
     Material(String, Vec<(MaterialTextureRefUsage, Image)>),
+    /// An asset for preview, along with the issues found in its data.
+    Issues(Vec<AssetIssue>, Box<AssetPreview>),
+    /// An asset for preview, along with the tree of nodes it was read from.
+    Structure(Vec<AssetTreeNode>, Box<AssetPreview>),
+    /// An asset for preview, along with the metadata fields of the file it came from.
+    Metadata(Vec<(String, String)>, Box<AssetPreview>),
 }
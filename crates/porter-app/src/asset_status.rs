@@ -50,6 +50,12 @@ impl AssetStatus {
         status: AtomicUsize::new(5),
     };
 
+    /// The asset changed on disk since it was loaded.
+    #[allow(clippy::declare_interior_mutable_const)]
+    pub const CHANGED: Self = Self {
+        status: AtomicUsize::new(6),
+    };
+
     /// Returns true if the status matches.
     #[inline]
     pub fn is(&self, status: Self) -> bool {
@@ -78,6 +84,7 @@ impl AssetStatus {
             3 => palette::ASSET_STATUS_PLACEHOLDER,
             4 => palette::ASSET_STATUS_EXPORTING,
             5 => palette::ASSET_STATUS_NOT_SUPPORTED,
+            6 => palette::ASSET_STATUS_CHANGED,
             _ => Color::WHITE,
         }
     }
@@ -92,6 +99,7 @@ impl fmt::Display for AssetStatus {
             3 => write!(f, "Placeholder"),
             4 => write!(f, "Exporting..."),
             5 => write!(f, "Not Supported"),
+            6 => write!(f, "Changed"),
             _ => write!(f, "<unset>"),
         }
     }
@@ -0,0 +1,39 @@
+/// A node of the data an asset was read from, shown in the preview structure tab.
+#[derive(Debug, Clone)]
+pub struct AssetTreeNode {
+    /// A short description of the node, such as its type and hash.
+    pub label: String,
+    /// The properties of the node.
+    pub properties: Vec<AssetTreeProperty>,
+    /// The child nodes of the node.
+    pub children: Vec<AssetTreeNode>,
+}
+
+/// A property of an asset tree node.
+#[derive(Debug, Clone)]
+pub struct AssetTreeProperty {
+    /// The name of the property.
+    pub name: String,
+    /// The type of the values.
+    pub kind: String,
+    /// The total number of values.
+    pub count: usize,
+    /// The first few values, formatted for display.
+    pub values: Vec<String>,
+}
+
+impl AssetTreeNode {
+    /// Constructs a new asset tree node with the given label.
+    pub fn new<L: Into<String>>(label: L) -> Self {
+        Self {
+            label: label.into(),
+            properties: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// The number of nodes in this subtree, including this node.
+    pub fn node_count(&self) -> usize {
+        1 + self.children.iter().map(Self::node_count).sum::<usize>()
+    }
+}
@@ -0,0 +1,50 @@
+use porter_math::Vector3;
+
+use porter_model::Model;
+
+/// The names of every blend shape in the model, in mesh order.
+pub fn blend_shape_names(model: &Model) -> Vec<String> {
+    model
+        .meshes
+        .iter()
+        .flat_map(|mesh| mesh.blend_shapes.iter())
+        .map(|blend_shape| blend_shape.name.clone())
+        .collect()
+}
+
+/// Deforms the model vertex positions by the given blend shape weights, in mesh order.
+///
+/// Blend shapes without a matching weight are left at zero.
+pub fn apply_blend_shapes(model: &mut Model, weights: &[f32]) {
+    let mut weights = weights.iter().copied();
+
+    for mesh in &mut model.meshes {
+        let blend_shapes = std::mem::take(&mut mesh.blend_shapes);
+
+        for blend_shape in &blend_shapes {
+            let weight = weights.next().unwrap_or_default() * blend_shape.target_scale;
+
+            if weight == 0.0 {
+                continue;
+            }
+
+            for (index, delta) in &blend_shape.vertex_deltas {
+                let index = *index as usize;
+
+                if index >= mesh.vertices.len() {
+                    continue;
+                }
+
+                let position = mesh.vertices.vertex(index).position();
+
+                mesh.vertices.vertex_mut(index).set_position(Vector3::new(
+                    position.x + delta.x * weight,
+                    position.y + delta.y * weight,
+                    position.z + delta.z * weight,
+                ));
+            }
+        }
+
+        mesh.blend_shapes = blend_shapes;
+    }
+}
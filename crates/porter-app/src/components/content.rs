@@ -94,9 +94,11 @@ impl Content {
     }
 
     /// Occurs when the user toggles the preview.
-    fn on_preview_toggle(&mut self, _: &mut AppState) -> Task<Message> {
+    fn on_preview_toggle(&mut self, state: &mut AppState) -> Task<Message> {
         if self.preview.is_some() {
             self.preview = None;
+            state.animation_playing = false;
+            state.pose_pending = false;
             Task::none()
         } else {
             self.preview = Some(Preview::new());
@@ -105,8 +107,10 @@ impl Content {
     }
 
     /// Occurs when the user wants to expand preview to a new window.
-    fn on_preview_window(&mut self, _: &mut AppState) -> Task<Message> {
+    fn on_preview_window(&mut self, state: &mut AppState) -> Task<Message> {
         self.preview = None;
+        state.animation_playing = false;
+        state.pose_pending = false;
 
         Task::done(Message::PreviewWindowCreate)
     }
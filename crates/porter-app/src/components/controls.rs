@@ -111,7 +111,7 @@ impl Controls {
                 );
         }
 
-        if state.exporting {
+        if state.exporting || state.loading {
             row = row
                 .push(
                     widgets::button(if state.export_canceled {
@@ -1,3 +1,8 @@
+use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Instant;
+
 use iced::border::Radius;
 use iced::border::rounded;
 
@@ -5,6 +10,7 @@ use iced::widget::Column;
 use iced::widget::Container;
 use iced::widget::column;
 use iced::widget::container;
+use iced::widget::mouse_area;
 use iced::widget::row;
 use iced::widget::scrollable;
 use iced::widget::stack;
@@ -20,11 +26,22 @@ use iced::Length;
 use iced::Task;
 use iced::Theme;
 
+use porter_model::Model;
+
 use porter_preview::PreviewError;
 
+use porter_texture::Image;
+
+use rfd::FileDialog;
+
+use crate::AnimationPlayer;
 use crate::AppState;
+use crate::AssetIssue;
 use crate::AssetPreview;
+use crate::AssetTreeNode;
 use crate::Message;
+use crate::apply_blend_shapes;
+use crate::blend_shape_names;
 use crate::fonts;
 use crate::palette;
 use crate::widgets;
@@ -33,6 +50,18 @@ use super::ContentMessage;
 
 /// Size of the header in pixels.
 const HEADER_HEIGHT: f32 = 30.0;
+/// Maximum number of uncovered bone names to list in the overlay.
+const MISSING_BONES_LISTED: usize = 3;
+/// Maximum number of ik handles to list in the overlay.
+const IK_HANDLES_LISTED: usize = 4;
+/// Maximum height of the blend shape panel in pixels.
+const BLEND_SHAPES_HEIGHT: f32 = 150.0;
+/// Size of a structure row in pixels.
+const STRUCTURE_ROW_HEIGHT: f32 = 22.0;
+/// Number of structure rows to render.
+const STRUCTURE_ROW_OVERSCAN: usize = 100;
+/// Indentation of each structure tree level in pixels.
+const STRUCTURE_INDENT: f32 = 16.0;
 /// Text to display when not supported.
 const TEXT_UNSUPPORTED: &str = "<This asset can't be represented as text>";
 
@@ -56,6 +85,35 @@ pub struct Preview {
     unsupported: bool,
     viewport_state: widgets::ViewportState,
     scroll_id: scrollable::Id,
+    model_loaded: bool,
+    animation: Option<AnimationPlayer>,
+    blend_model: Option<(String, Model, Arc<[Option<Image>]>)>,
+    blend_shapes: Vec<(String, f32)>,
+    /// The animation frame and blend shape weights of the pose last sent to the renderer.
+    shown_pose: Option<(f32, Vec<f32>)>,
+    /// The bones linked by each ik handle of the previewed model, drawn over the viewport.
+    ik_links: Vec<String>,
+    issues: Vec<AssetIssue>,
+    metadata: Vec<(String, String)>,
+    structure: Vec<AssetTreeNode>,
+    structure_expanded: HashSet<usize>,
+    structure_rows: Vec<StructureRow>,
+    structure_range: Range<usize>,
+}
+
+/// A visible row of the structure tab, either a node or one of its properties.
+enum StructureRow {
+    Node {
+        id: usize,
+        depth: usize,
+        label: String,
+        expandable: bool,
+        expanded: bool,
+    },
+    Property {
+        depth: usize,
+        text: String,
+    },
 }
 
 /// Currently active preview tab.
@@ -64,6 +122,9 @@ pub enum PreviewTab {
     Viewport,
     Text,
     Binary,
+    Structure,
+    Metadata,
+    Issues,
 }
 
 /// Messages produced by the preview component.
@@ -74,8 +135,20 @@ pub enum PreviewMessage {
     Text,
     TextAction(text_editor::Action),
     Binary,
+    Structure,
+    StructureToggle(usize),
+    StructureScroll(scrollable::Viewport),
+    Metadata,
+    Issues,
     Update(AssetPreview),
     Request,
+    AnimationLoad,
+    AnimationPlay,
+    AnimationPause,
+    AnimationScrub(f32),
+    AnimationLoop(bool),
+    AnimationTick(Instant),
+    BlendShapeWeight(usize, f32),
 }
 
 impl Preview {
@@ -90,6 +163,18 @@ impl Preview {
             unsupported: false,
             viewport_state: widgets::ViewportState::new(),
             scroll_id: scrollable::Id::unique(),
+            model_loaded: false,
+            animation: None,
+            blend_model: None,
+            blend_shapes: Vec::new(),
+            shown_pose: None,
+            ik_links: Vec::new(),
+            issues: Vec::new(),
+            metadata: Vec::new(),
+            structure: Vec::new(),
+            structure_expanded: HashSet::new(),
+            structure_rows: Vec::new(),
+            structure_range: 0..0,
         }
     }
 
@@ -103,8 +188,20 @@ impl Preview {
             Text => self.on_text(state),
             TextAction(action) => self.on_text_action(state, action),
             Binary => self.on_binary(state),
+            Structure => self.on_structure(state),
+            StructureToggle(id) => self.on_structure_toggle(state, id),
+            StructureScroll(viewport) => self.on_structure_scroll(state, viewport),
+            Metadata => self.on_metadata(state),
+            Issues => self.on_issues(state),
             Update(asset) => self.on_preview_update(state, asset),
             Request => self.on_preview_request(state),
+            AnimationLoad => self.on_animation_load(state),
+            AnimationPlay => self.on_animation_play(state),
+            AnimationPause => self.on_animation_pause(state),
+            AnimationScrub(frame) => self.on_animation_scrub(state, frame),
+            AnimationLoop(looping) => self.on_animation_loop(state, looping),
+            AnimationTick(now) => self.on_animation_tick(state, now),
+            BlendShapeWeight(index, weight) => self.on_blend_shape_weight(state, index, weight),
         }
     }
 
@@ -221,6 +318,45 @@ impl Preview {
                             .spacing(8.0),
                         );
                     }
+
+                    if let Some(animation) = &self.animation {
+                        columns = columns.push(preview_stat("Anim", animation.name()));
+                        columns = columns.push(preview_stat(
+                            "Frame",
+                            format!("{:.0}/{:.0}", animation.frame(), animation.frame_count()),
+                        ));
+
+                        let missing_bones = animation.missing_bones();
+
+                        if !missing_bones.is_empty() {
+                            let mut listed = missing_bones
+                                .iter()
+                                .take(MISSING_BONES_LISTED)
+                                .map(String::as_str)
+                                .collect::<Vec<_>>()
+                                .join(", ");
+
+                            if missing_bones.len() > MISSING_BONES_LISTED {
+                                listed.push_str(", ...");
+                            }
+
+                            columns = columns.push(preview_stat(
+                                "Static",
+                                format!("{} ({})", missing_bones.len(), listed),
+                            ));
+                        }
+                    }
+
+                    for link in self.ik_links.iter().take(IK_HANDLES_LISTED) {
+                        columns = columns.push(preview_stat("IK", link));
+                    }
+
+                    if self.ik_links.len() > IK_HANDLES_LISTED {
+                        columns = columns.push(preview_stat(
+                            "IK",
+                            format!("... {} more", self.ik_links.len() - IK_HANDLES_LISTED),
+                        ));
+                    }
                 }
 
                 let columns = container(
@@ -291,7 +427,21 @@ impl Preview {
                         .height(Length::Fill)
                 };
 
-                Element::from(stack([viewport, overlay.into()]))
+                let viewport = Element::from(stack([viewport, overlay.into()]));
+
+                if self.model_loaded {
+                    let mut viewport = column([viewport, self.animation_controls()])
+                        .width(Length::Fill)
+                        .height(Length::Fill);
+
+                    if !self.blend_shapes.is_empty() {
+                        viewport = viewport.push(self.blend_shape_controls());
+                    }
+
+                    viewport.into()
+                } else {
+                    viewport
+                }
             }
             PreviewTab::Text => Element::from(
                 widgets::scrollable(
@@ -325,6 +475,9 @@ impl Preview {
                     .width(Length::Fill)
                     .height(Length::Fill),
             ),
+            PreviewTab::Structure => self.structure_view(),
+            PreviewTab::Metadata => self.metadata_view(),
+            PreviewTab::Issues => self.issues_view(),
         };
 
         let footer: Option<Container<_>> =
@@ -469,6 +622,82 @@ impl Preview {
                 None
             })
             .into(),
+            widgets::tab(
+                row([
+                    text("#")
+                        .size(16.0)
+                        .height(Length::Shrink)
+                        .align_y(Alignment::Center)
+                        .into(),
+                    text("Structure")
+                        .height(Length::Shrink)
+                        .align_y(Alignment::Center)
+                        .into(),
+                ])
+                .height(Length::Shrink)
+                .spacing(8.0),
+                matches!(self.tab, PreviewTab::Structure),
+            )
+            .width(Length::Shrink)
+            .height(Length::Shrink)
+            .on_press_maybe(if self.structure.is_empty() {
+                None
+            } else {
+                Some(Message::from(PreviewMessage::Structure))
+            })
+            .into(),
+            widgets::tab(
+                row([
+                    text("i")
+                        .size(16.0)
+                        .height(Length::Shrink)
+                        .align_y(Alignment::Center)
+                        .into(),
+                    text("Metadata")
+                        .height(Length::Shrink)
+                        .align_y(Alignment::Center)
+                        .into(),
+                ])
+                .height(Length::Shrink)
+                .spacing(8.0),
+                matches!(self.tab, PreviewTab::Metadata),
+            )
+            .width(Length::Shrink)
+            .height(Length::Shrink)
+            .on_press_maybe(if self.metadata.is_empty() {
+                None
+            } else {
+                Some(Message::from(PreviewMessage::Metadata))
+            })
+            .into(),
+            widgets::tab(
+                row([
+                    text("!")
+                        .size(16.0)
+                        .height(Length::Shrink)
+                        .align_y(Alignment::Center)
+                        .into(),
+                    text(if self.issues.is_empty() {
+                        String::from("Issues")
+                    } else {
+                        format!("Issues ({})", self.issues.len())
+                    })
+                    .height(Length::Shrink)
+                    .align_y(Alignment::Center)
+                    .into(),
+                ])
+                .height(Length::Shrink)
+                .spacing(8.0),
+                matches!(self.tab, PreviewTab::Issues),
+            )
+            .width(Length::Shrink)
+            .height(Length::Shrink)
+            .on_press_maybe(if self.issues.is_empty() {
+                None
+            } else {
+                Some(Message::from(PreviewMessage::Issues))
+            })
+            .into(),
         ])
         .width(Length::Fill)
         .height(Length::Shrink)
@@ -561,9 +790,201 @@ impl Preview {
         Task::none()
     }
 
+    /// Occurs when the structure tab is clicked.
+    fn on_structure(&mut self, _state: &mut AppState) -> Task<Message> {
+        if matches!(self.tab, PreviewTab::Structure) {
+            return Task::none();
+        }
+
+        self.tab = PreviewTab::Structure;
+        self.structure_range = 0..STRUCTURE_ROW_OVERSCAN.min(self.structure_rows.len());
+
+        scrollable::scroll_to(
+            self.scroll_id.clone(),
+            scrollable::AbsoluteOffset { x: 0.0, y: 0.0 },
+        )
+    }
+
+    /// Occurs when a structure node is expanded or collapsed.
+    fn on_structure_toggle(&mut self, _state: &mut AppState, id: usize) -> Task<Message> {
+        if !self.structure_expanded.remove(&id) {
+            self.structure_expanded.insert(id);
+        }
+
+        self.rebuild_structure_rows();
+
+        let end =
+            (self.structure_range.start + STRUCTURE_ROW_OVERSCAN).min(self.structure_rows.len());
+
+        self.structure_range = self.structure_range.start.min(end)..end;
+
+        Task::none()
+    }
+
+    /// Occurs when the user scrolls the structure tree.
+    fn on_structure_scroll(
+        &mut self,
+        _state: &mut AppState,
+        viewport: scrollable::Viewport,
+    ) -> Task<Message> {
+        let rows = self.structure_rows.len();
+
+        let mut start = (viewport.absolute_offset().y / STRUCTURE_ROW_HEIGHT).floor() as usize;
+
+        if start + STRUCTURE_ROW_OVERSCAN > rows {
+            start = start.saturating_sub((start + STRUCTURE_ROW_OVERSCAN) - rows);
+        }
+
+        self.structure_range = start..(start + STRUCTURE_ROW_OVERSCAN).min(rows);
+
+        Task::none()
+    }
+
+    /// Flattens the expanded parts of the structure tree into rows.
+    fn rebuild_structure_rows(&mut self) {
+        fn push_node(
+            rows: &mut Vec<StructureRow>,
+            expanded: &HashSet<usize>,
+            node: &AssetTreeNode,
+            depth: usize,
+            id: usize,
+        ) {
+            let is_expanded = expanded.contains(&id);
+
+            rows.push(StructureRow::Node {
+                id,
+                depth,
+                label: node.label.clone(),
+                expandable: !node.properties.is_empty() || !node.children.is_empty(),
+                expanded: is_expanded,
+            });
+
+            if !is_expanded {
+                return;
+            }
+
+            for property in &node.properties {
+                let mut text = format!(
+                    "{} ({}, {}): {}",
+                    property.name,
+                    property.kind,
+                    property.count,
+                    property.values.join(", ")
+                );
+
+                if property.count > property.values.len() {
+                    text.push_str(", ...");
+                }
+
+                rows.push(StructureRow::Property {
+                    depth: depth + 1,
+                    text,
+                });
+            }
+
+            // Node ids are their position in a walk of the whole tree.
+            let mut child_id = id + 1;
+
+            for child in &node.children {
+                push_node(rows, expanded, child, depth + 1, child_id);
+
+                child_id += child.node_count();
+            }
+        }
+
+        let mut rows = Vec::new();
+        let mut id = 0;
+
+        for node in &self.structure {
+            push_node(&mut rows, &self.structure_expanded, node, 0, id);
+
+            id += node.node_count();
+        }
+
+        self.structure_rows = rows;
+    }
+
+    /// Occurs when the metadata tab is clicked.
+    fn on_metadata(&mut self, _state: &mut AppState) -> Task<Message> {
+        if matches!(self.tab, PreviewTab::Metadata) {
+            return Task::none();
+        }
+
+        self.tab = PreviewTab::Metadata;
+
+        Task::none()
+    }
+
+    /// Occurs when the issues tab is clicked.
+    fn on_issues(&mut self, _state: &mut AppState) -> Task<Message> {
+        if matches!(self.tab, PreviewTab::Issues) {
+            return Task::none();
+        }
+
+        self.tab = PreviewTab::Issues;
+
+        Task::none()
+    }
+
     /// Occurs when the asset manager has a new asset to preview.
-    fn on_preview_update(&mut self, _: &mut AppState, asset: AssetPreview) -> Task<Message> {
+    fn on_preview_update(&mut self, state: &mut AppState, asset: AssetPreview) -> Task<Message> {
+        self.issues.clear();
+        self.metadata.clear();
+        self.structure.clear();
+        self.structure_expanded.clear();
+        self.structure_rows.clear();
+        self.structure_range = 0..0;
+        self.model_loaded = false;
+        self.animation = None;
+        self.blend_model = None;
+        self.blend_shapes.clear();
+        self.shown_pose = None;
+        self.ik_links.clear();
+
+        state.animation_playing = false;
+        state.pose_pending = false;
+
         match asset {
+            AssetPreview::Structure(structure, asset) => {
+                let task = self.on_preview_update(state, *asset);
+
+                // Top level nodes start expanded, so there's something to look at.
+                let mut id = 0;
+
+                for node in &structure {
+                    self.structure_expanded.insert(id);
+
+                    id += node.node_count();
+                }
+
+                self.structure = structure;
+                self.rebuild_structure_rows();
+                self.structure_range = 0..STRUCTURE_ROW_OVERSCAN.min(self.structure_rows.len());
+
+                return task;
+            }
+            AssetPreview::Metadata(metadata, asset) => {
+                let task = self.on_preview_update(state, *asset);
+
+                self.metadata = metadata;
+
+                return task;
+            }
+            AssetPreview::Issues(mut issues, asset) => {
+                let task = self.on_preview_update(state, *asset);
+
+                // Errors first, keeping the order they were found in.
+                issues.sort_by(|a, b| b.severity.cmp(&a.severity));
+
+                // The asset couldn't be shown, so show why instead.
+                if self.error {
+                    self.tab = PreviewTab::Issues;
+                }
+
+                self.issues = issues;
+
+                return task;
+            }
             AssetPreview::NotSupported => {
                 self.raw_text = text_editor::Content::new();
                 self.raw_binary = None;
@@ -673,6 +1094,18 @@ impl Preview {
 
                 let srgb = cfg!(feature = "srgb-preview");
 
+                let blend_shapes = blend_shape_names(&model);
+                let ik_links = ik_handle_links(&model);
+
+                // Keep the undeformed model around so that shape weights can be reapplied, sharing its images.
+                let (images, blend_model) = if blend_shapes.is_empty() {
+                    (images, None)
+                } else {
+                    let images: Arc<[Option<Image>]> = images.into();
+
+                    (images.to_vec(), Some((name.clone(), model.clone(), images)))
+                };
+
                 if let Err(e) = self
                     .viewport_state
                     .renderer_mut()
@@ -688,6 +1121,48 @@ impl Preview {
                 } else {
                     self.unsupported = false;
                     self.error = false;
+                    self.model_loaded = true;
+                    self.blend_model = blend_model;
+                    self.blend_shapes = blend_shapes.into_iter().map(|name| (name, 0.0)).collect();
+                    self.shown_pose = Some((0.0, vec![0.0; self.blend_shapes.len()]));
+                    self.ik_links = ik_links;
+                }
+
+                self.tab = PreviewTab::Viewport;
+            }
+            AssetPreview::AnimatedModel(name, model, images, animation) => {
+                self.raw_text = text_editor::Content::new();
+                self.raw_binary = None;
+                self.raw_name = String::new();
+
+                let animation = AnimationPlayer::new(name, model, images.into(), animation);
+
+                let srgb = cfg!(feature = "srgb-preview");
+
+                if let Err(e) = self.viewport_state.renderer_mut().set_preview_model(
+                    animation.name().to_string(),
+                    animation.pose(&[]),
+                    animation.images().to_vec(),
+                    srgb,
+                ) {
+                    if matches!(e, PreviewError::Unsupported) {
+                        self.unsupported = true;
+                        self.error = false;
+                    } else {
+                        self.unsupported = false;
+                        self.error = true;
+                    }
+                } else {
+                    self.unsupported = false;
+                    self.error = false;
+                    self.model_loaded = true;
+                    self.blend_shapes = blend_shape_names(animation.model())
+                        .into_iter()
+                        .map(|name| (name, 0.0))
+                        .collect();
+                    self.shown_pose = Some((animation.frame(), vec![0.0; self.blend_shapes.len()]));
+                    self.ik_links = ik_handle_links(animation.model());
+                    self.animation = Some(animation);
                 }
 
                 self.tab = PreviewTab::Viewport;
@@ -701,6 +1176,471 @@ impl Preview {
     fn on_preview_request(&mut self, _: &mut AppState) -> Task<Message> {
         Task::done(Message::PreviewRequest)
     }
+
+    /// Occurs when the user wants to pick an animation to play on the previewed model.
+    fn on_animation_load(&mut self, state: &mut AppState) -> Task<Message> {
+        let mut file_dialog = FileDialog::new().set_title("Select an animation to preview");
+
+        for (name, extensions) in &state.file_filters {
+            file_dialog = file_dialog.add_filter(*name, extensions);
+        }
+
+        let controller = state.controller.clone();
+
+        let dialog = move || {
+            if let Some(file) = file_dialog.pick_file() {
+                controller.preview_animation(file);
+            }
+        };
+
+        #[cfg(target_os = "windows")]
+        std::thread::spawn(dialog);
+
+        #[cfg(not(target_os = "windows"))]
+        dialog();
+
+        Task::none()
+    }
+
+    /// Occurs when the user starts playing the animation.
+    fn on_animation_play(&mut self, state: &mut AppState) -> Task<Message> {
+        if let Some(animation) = &mut self.animation {
+            animation.play();
+
+            state.animation_playing = true;
+        }
+
+        Task::none()
+    }
+
+    /// Occurs when the user pauses the animation.
+    fn on_animation_pause(&mut self, state: &mut AppState) -> Task<Message> {
+        if let Some(animation) = &mut self.animation {
+            animation.pause();
+        }
+
+        state.animation_playing = false;
+
+        Task::none()
+    }
+
+    /// Occurs when the user scrubs the animation to a specific frame.
+    fn on_animation_scrub(&mut self, state: &mut AppState, frame: f32) -> Task<Message> {
+        if let Some(animation) = &mut self.animation {
+            animation.scrub(frame);
+        }
+
+        state.pose_pending = true;
+
+        Task::none()
+    }
+
+    /// Occurs when the user toggles animation looping.
+    fn on_animation_loop(&mut self, _: &mut AppState, looping: bool) -> Task<Message> {
+        if let Some(animation) = &mut self.animation {
+            animation.set_looping(looping);
+        }
+
+        Task::none()
+    }
+
+    /// Occurs every frame while the animation is playing.
+    fn on_animation_tick(&mut self, state: &mut AppState, now: Instant) -> Task<Message> {
+        match &mut self.animation {
+            Some(animation) => {
+                animation.tick(now);
+
+                state.animation_playing = animation.is_playing();
+            }
+            None => state.animation_playing = false,
+        }
+
+        // However many scrubs, slider moves and ticks happened since the last frame, the pose is uploaded once.
+        state.pose_pending = false;
+
+        self.update_model_pose();
+
+        Task::none()
+    }
+
+    /// Occurs when the user changes the weight of a blend shape.
+    fn on_blend_shape_weight(
+        &mut self,
+        state: &mut AppState,
+        index: usize,
+        weight: f32,
+    ) -> Task<Message> {
+        if let Some((_, blend_weight)) = self.blend_shapes.get_mut(index) {
+            *blend_weight = weight;
+        }
+
+        state.pose_pending = true;
+
+        Task::none()
+    }
+
+    /// Uploads the model posed at the current animation frame and blend shape weights to the renderer.
+    ///
+    /// The renderer only takes whole models with images it owns, so the model and a copy of the shared images are
+    /// sent again, but only when the pose differs from the one shown.
+    fn update_model_pose(&mut self) {
+        let weights: Vec<f32> = self
+            .blend_shapes
+            .iter()
+            .map(|(_, weight)| *weight)
+            .collect();
+
+        let frame = self
+            .animation
+            .as_ref()
+            .map(|animation| animation.frame())
+            .unwrap_or_default();
+
+        if self
+            .shown_pose
+            .as_ref()
+            .is_some_and(|(shown_frame, shown_weights)| {
+                *shown_frame == frame && *shown_weights == weights
+            })
+        {
+            return;
+        }
+
+        let (name, model, images) = if let Some(animation) = &self.animation {
+            (
+                animation.name().to_string(),
+                animation.pose(&weights),
+                animation.images().to_vec(),
+            )
+        } else if let Some((name, model, images)) = &self.blend_model {
+            let mut model = model.clone();
+
+            apply_blend_shapes(&mut model, &weights);
+
+            (name.clone(), model, images.to_vec())
+        } else {
+            return;
+        };
+
+        let srgb = cfg!(feature = "srgb-preview");
+
+        let _ = self
+            .viewport_state
+            .renderer_mut()
+            .set_preview_model(name, model, images, srgb);
+
+        self.shown_pose = Some((frame, weights));
+    }
+
+    /// Renders the visible rows of the structure tree, with gaps standing in for the rows out of view.
+    fn structure_view(&self) -> Element<'_, Message> {
+        let range = self.structure_range.clone();
+
+        let top_gap = vertical_space().height(range.start as f32 * STRUCTURE_ROW_HEIGHT);
+        let bottom_gap = vertical_space().height(
+            self.structure_rows.len().saturating_sub(range.end) as f32 * STRUCTURE_ROW_HEIGHT,
+        );
+
+        let mut rows: Column<_> = Column::with_capacity(STRUCTURE_ROW_OVERSCAN + 2)
+            .width(Length::Fill)
+            .padding([0.0, 20.0, 0.0, 4.0]);
+
+        rows = rows.push(top_gap);
+
+        for structure_row in &self.structure_rows[range] {
+            let row: Element<_> = match structure_row {
+                StructureRow::Node {
+                    id,
+                    depth,
+                    label,
+                    expandable,
+                    expanded,
+                } => {
+                    let toggle = match (expandable, expanded) {
+                        (false, _) => " ",
+                        (true, false) => "+",
+                        (true, true) => "-",
+                    };
+
+                    mouse_area(
+                        container(
+                            text(format!("{toggle} {label}"))
+                                .font(fonts::MONOSPACE_FONT)
+                                .wrapping(text::Wrapping::None),
+                        )
+                        .padding([0.0, 0.0, 0.0, *depth as f32 * STRUCTURE_INDENT])
+                        .width(Length::Fill)
+                        .height(Length::Fixed(STRUCTURE_ROW_HEIGHT))
+                        .align_y(Alignment::Center)
+                        .clip(true),
+                    )
+                    .on_press(Message::from(PreviewMessage::StructureToggle(*id)))
+                    .into()
+                }
+                StructureRow::Property { depth, text: value } => container(
+                    text(value)
+                        .font(fonts::MONOSPACE_FONT)
+                        .color(palette::TEXT_COLOR_SECONDARY)
+                        .wrapping(text::Wrapping::None),
+                )
+                .padding([0.0, 0.0, 0.0, *depth as f32 * STRUCTURE_INDENT])
+                .width(Length::Fill)
+                .height(Length::Fixed(STRUCTURE_ROW_HEIGHT))
+                .align_y(Alignment::Center)
+                .clip(true)
+                .into(),
+            };
+
+            rows = rows.push(row);
+        }
+
+        rows = rows.push(bottom_gap);
+
+        Element::from(
+            widgets::scrollable(rows)
+                .id(self.scroll_id.clone())
+                .on_scroll(|viewport| Message::from(PreviewMessage::StructureScroll(viewport)))
+                .direction(scrollable::Direction::Vertical(
+                    scrollable::Scrollbar::new()
+                        .width(16.0)
+                        .scroller_width(16.0)
+                        .spacing(0.0),
+                ))
+                .width(Length::Fill)
+                .height(Length::Fill),
+        )
+    }
+
+    /// Renders every metadata field of the previewed asset's file.
+    fn metadata_view(&self) -> Element<'_, Message> {
+        let mut fields: Column<_> = Column::with_capacity(self.metadata.len())
+            .width(Length::Fill)
+            .padding([4.0, 20.0, 4.0, 8.0])
+            .spacing(4.0);
+
+        for (name, value) in &self.metadata {
+            fields = fields.push(
+                row([
+                    text(name)
+                        .width(Length::Fixed(150.0))
+                        .color(palette::TEXT_COLOR_SECONDARY)
+                        .into(),
+                    text(value).width(Length::Fill).into(),
+                ])
+                .spacing(8.0),
+            );
+        }
+
+        Element::from(
+            widgets::scrollable(fields)
+                .id(self.scroll_id.clone())
+                .direction(scrollable::Direction::Vertical(
+                    scrollable::Scrollbar::new()
+                        .width(16.0)
+                        .scroller_width(16.0)
+                        .spacing(0.0),
+                ))
+                .width(Length::Fill)
+                .height(Length::Fill),
+        )
+    }
+
+    /// Renders every issue found in the previewed asset.
+    fn issues_view(&self) -> Element<'_, Message> {
+        let mut issues: Column<_> = Column::with_capacity(self.issues.len())
+            .width(Length::Fill)
+            .padding([4.0, 20.0, 4.0, 8.0])
+            .spacing(4.0);
+
+        for issue in &self.issues {
+            issues = issues.push(
+                row([
+                    text(issue.severity.to_string())
+                        .width(Length::Fixed(70.0))
+                        .color(if issue.is_error() {
+                            palette::TEXT_COLOR_WARN
+                        } else {
+                            palette::TEXT_COLOR_SECONDARY
+                        })
+                        .into(),
+                    text(issue.location())
+                        .width(Length::FillPortion(1))
+                        .font(fonts::MONOSPACE_FONT)
+                        .color(palette::TEXT_COLOR_SECONDARY)
+                        .into(),
+                    text(&issue.message).width(Length::FillPortion(2)).into(),
+                ])
+                .spacing(8.0),
+            );
+        }
+
+        Element::from(
+            widgets::scrollable(issues)
+                .id(self.scroll_id.clone())
+                .direction(scrollable::Direction::Vertical(
+                    scrollable::Scrollbar::new()
+                        .width(16.0)
+                        .scroller_width(16.0)
+                        .spacing(0.0),
+                ))
+                .width(Length::Fill)
+                .height(Length::Fill),
+        )
+    }
+
+    /// Renders a weight slider for every blend shape below the viewport.
+    fn blend_shape_controls(&self) -> Element<'_, Message> {
+        let mut sliders: Column<_> = Column::with_capacity(self.blend_shapes.len())
+            .width(Length::Fill)
+            .padding([0.0, 20.0, 0.0, 0.0])
+            .spacing(2.0);
+
+        for (index, (name, weight)) in self.blend_shapes.iter().enumerate() {
+            sliders = sliders.push(
+                row([
+                    container(text(name).wrapping(text::Wrapping::None))
+                        .clip(true)
+                        .width(Length::Fixed(150.0))
+                        .into(),
+                    widgets::slider(0.0..=1.0, *weight, move |weight| {
+                        Message::from(PreviewMessage::BlendShapeWeight(index, weight))
+                    })
+                    .step(0.01)
+                    .width(Length::Fill)
+                    .into(),
+                    text(format!("{:.2}", weight))
+                        .font(fonts::MONOSPACE_FONT)
+                        .into(),
+                ])
+                .spacing(8.0)
+                .align_y(Alignment::Center),
+            );
+        }
+
+        container(
+            widgets::scrollable(sliders)
+                .direction(scrollable::Direction::Vertical(
+                    scrollable::Scrollbar::new()
+                        .width(16.0)
+                        .scroller_width(16.0)
+                        .spacing(0.0),
+                ))
+                .width(Length::Fill)
+                .height(Length::Shrink),
+        )
+        .width(Length::Fill)
+        .max_height(BLEND_SHAPES_HEIGHT)
+        .padding(4.0)
+        .style(preview_content_style)
+        .into()
+    }
+
+    /// Renders the animation playback controls below the viewport.
+    fn animation_controls(&self) -> Element<'_, Message> {
+        let load = widgets::button(text("Load Animation"))
+            .on_press(Message::from(PreviewMessage::AnimationLoad))
+            .into();
+
+        let Some(animation) = &self.animation else {
+            return container(row([load]).align_y(Alignment::Center))
+                .width(Length::Fill)
+                .padding(4.0)
+                .style(preview_content_style)
+                .into();
+        };
+
+        let play = if animation.is_playing() {
+            widgets::button(text("Pause")).on_press(Message::from(PreviewMessage::AnimationPause))
+        } else {
+            widgets::button(text("Play")).on_press(Message::from(PreviewMessage::AnimationPlay))
+        };
+
+        container(
+            row([
+                load,
+                play.into(),
+                widgets::slider(0.0..=animation.frame_count(), animation.frame(), |frame| {
+                    Message::from(PreviewMessage::AnimationScrub(frame))
+                })
+                .step(1.0)
+                .width(Length::Fill)
+                .into(),
+                widgets::checkbox("Loop", animation.is_looping())
+                    .on_toggle(|looping| Message::from(PreviewMessage::AnimationLoop(looping)))
+                    .into(),
+                text(format!(
+                    "{:.0} / {:.0}",
+                    animation.frame(),
+                    animation.frame_count()
+                ))
+                .font(fonts::MONOSPACE_FONT)
+                .into(),
+            ])
+            .spacing(8.0)
+            .align_y(Alignment::Center),
+        )
+        .width(Length::Fill)
+        .padding(4.0)
+        .style(preview_content_style)
+        .into()
+    }
+}
+
+/// Renders a single statistic row for the preview overlay.
+fn preview_stat<'a>(header: impl ToString, value: impl ToString) -> Element<'a, Message> {
+    row([
+        text(header.to_string())
+            .size(16.0)
+            .width(75.0)
+            .color(palette::TEXT_COLOR_INFO)
+            .font(fonts::MONOSPACE_BOLD_FONT)
+            .into(),
+        text(":")
+            .size(16.0)
+            .color(palette::TEXT_COLOR_INFO)
+            .font(fonts::MONOSPACE_BOLD_FONT)
+            .into(),
+        text(value.to_string())
+            .size(16.0)
+            .color(palette::TEXT_COLOR_DEFAULT)
+            .font(fonts::MONOSPACE_BOLD_FONT)
+            .into(),
+    ])
+    .width(Length::Shrink)
+    .padding(2.0)
+    .spacing(8.0)
+    .into()
+}
+
+/// Describes the bones each ik handle of the model links, from the start to the end bone and on to the pole bone.
+fn ik_handle_links(model: &Model) -> Vec<String> {
+    let bones = &model.skeleton.bones;
+    let bone_name = |index: usize| {
+        bones
+            .get(index)
+            .and_then(|bone| bone.name.clone())
+            .unwrap_or_else(|| format!("bone_{index}"))
+    };
+
+    model
+        .skeleton
+        .ik_handles
+        .iter()
+        .map(|ik_handle| {
+            let mut link = format!(
+                "{}: {} -> {}",
+                ik_handle.name.as_deref().unwrap_or("<unnamed>"),
+                bone_name(ik_handle.start_bone),
+                bone_name(ik_handle.end_bone)
+            );
+
+            if let Some(pole) = ik_handle.pole_bone.or(ik_handle.pole_vector_bone) {
+                link.push_str(&format!(" -> {}", bone_name(pole)));
+            }
+
+            link
+        })
+        .collect()
 }
 
 /// Style for the preview header.
@@ -34,6 +34,8 @@ pub enum SettingsMessage {
     Save(crate::Settings),
     PickExportFolder,
     OpenExportFolder,
+    PickTextureFolder,
+    RemoveTextureFolder(usize),
     OpenConfigFolder,
     ApplyCustomScale,
     ScaleInput(String),
@@ -53,6 +55,8 @@ impl Settings {
             Save(settings) => self.on_save(state, settings),
             PickExportFolder => self.on_pick_export_folder(state),
             OpenExportFolder => self.on_open_export_folder(state),
+            PickTextureFolder => self.on_pick_texture_folder(state),
+            RemoveTextureFolder(index) => self.on_remove_texture_folder(state, index),
             OpenConfigFolder => self.on_open_config_folder(state),
             ApplyCustomScale => self.on_apply_custom_scale(state),
             ScaleInput(input) => self.on_scale_input(state, input),
@@ -61,12 +65,6 @@ impl Settings {
 
     /// Handles rendering the settings component.
     pub fn view<'a>(&'a self, state: &'a AppState) -> Element<'a, Message> {
-        #[cfg(feature = "raw-files-forcible")]
-        {
-            let model_formats = state.settings.model_file_types();
-            let model_format_enabled = |format: ModelFileType| model_formats.contains(&format);
-        }
-
         let save_message =
             |settings: crate::Settings| Message::from(SettingsMessage::Save(settings));
 
@@ -74,6 +72,9 @@ impl Settings {
 
         #[cfg(feature = "raw-files-forcible")]
         {
+            let model_formats = state.settings.model_file_types();
+            let model_format_enabled = |format: ModelFileType| model_formats.contains(&format);
+
             settings = settings.extend([
                 text("Settings - General")
                     .size(20.0)
@@ -334,6 +335,23 @@ impl Settings {
                         }))
                     })
                     .into(),
+                vertical_space().height(2.0).into(),
+                text("Choose how to export hair:")
+                    .color(palette::TEXT_COLOR_SECONDARY)
+                    .into(),
+                vertical_space().height(0.0).into(),
+                widgets::checkbox(
+                    "Convert hair to meshes",
+                    state.settings.export_hair_as_meshes(),
+                )
+                .on_toggle(move |value| {
+                    save_message(
+                        state
+                            .settings
+                            .update(|settings| settings.set_export_hair_as_meshes(value)),
+                    )
+                })
+                .into(),
                 vertical_space().height(4.0).into(),
                 text("Settings - Images")
                     .size(20.0)
@@ -394,6 +412,38 @@ impl Settings {
                 }
             }
 
+            if state.asset_manager.supports_texture_directories() {
+                settings = settings.extend([
+                    vertical_space().height(2.0).into(),
+                    text("Folders searched for textures that aren't next to the asset:")
+                        .color(palette::TEXT_COLOR_SECONDARY)
+                        .into(),
+                    vertical_space().height(0.0).into(),
+                ]);
+
+                for (index, directory) in state.settings.texture_directories().iter().enumerate() {
+                    settings = settings.push(
+                        row(vec![
+                            widgets::text_input("Texture directory", &directory.to_string_lossy())
+                                .on_input(|_| Message::Noop)
+                                .width(500.0)
+                                .into(),
+                            widgets::button("Remove")
+                                .on_press(Message::from(SettingsMessage::RemoveTextureFolder(
+                                    index,
+                                )))
+                                .into(),
+                        ])
+                        .spacing(4.0),
+                    );
+                }
+
+                settings = settings.push(
+                    widgets::button("Add Folder")
+                        .on_press(Message::from(SettingsMessage::PickTextureFolder)),
+                );
+            }
+
             #[cfg(feature = "normal-maps-convertible")]
             {
                 use crate::ImageNormalMapProcessing;
@@ -619,6 +669,28 @@ impl Settings {
             ]);
         }
 
+        if state.asset_manager.supports_watching() {
+            settings = settings.extend([
+                vertical_space().height(2.0).into(),
+                text("Choose whether or not to reload loaded files when they change on disk:")
+                    .color(palette::TEXT_COLOR_SECONDARY)
+                    .into(),
+                vertical_space().height(0.0).into(),
+                widgets::checkbox(
+                    "Watch loaded files for changes",
+                    state.settings.watch_files(),
+                )
+                .on_toggle(move |value| {
+                    save_message(
+                        state
+                            .settings
+                            .update(|settings| settings.set_watch_files(value)),
+                    )
+                })
+                .into(),
+            ]);
+        }
+
         settings = settings.extend([
             vertical_space().height(2.0).into(),
             text("Troubleshooting options:")
@@ -656,9 +728,17 @@ impl Settings {
             state.reload_required = state.settings.reload_required(&settings);
         }
 
+        let watch_changed = state.settings.watch_files() != settings.watch_files();
+
         state.settings = settings;
         state.settings.save(state.name);
 
+        if watch_changed {
+            state
+                .asset_manager
+                .watch(state.settings.clone(), state.controller.clone());
+        }
+
         self.custom_scale = state.settings.custom_scale().map(format_custom_scale);
 
         Task::none()
@@ -676,6 +756,20 @@ impl Settings {
         Task::none()
     }
 
+    /// Allows the user to pick a folder to search for textures.
+    fn on_pick_texture_folder(&mut self, _: &mut AppState) -> Task<Message> {
+        Task::done(Message::from(MainMessage::PickTextureFolder))
+    }
+
+    /// Stops searching the texture folder at the given index.
+    fn on_remove_texture_folder(&mut self, state: &mut AppState, index: usize) -> Task<Message> {
+        let settings = state
+            .settings
+            .update(|settings| settings.remove_texture_directory(index));
+
+        self.on_save(state, settings)
+    }
+
     /// Opens the config folder.
     fn on_open_config_folder(&mut self, _: &mut AppState) -> Task<Message> {
         let Some(project_directory) = ProjectDirs::from("com", "DTZxPorter", "GameTools") else {
@@ -104,7 +104,7 @@ impl VirtualList {
 
     /// Handles rendering for the virtual list component.
     pub fn view(&self, state: &AppState) -> Element<'_, Message> {
-        let content: Element<Message> = if state.loading || state.asset_manager.assets_empty() {
+        let content: Element<Message> = if state.asset_manager.assets_empty() {
             if state.loading {
                 widgets::spinner().into()
             } else {
@@ -46,6 +46,15 @@ impl Controller {
         debug_assert!(result.is_ok());
     }
 
+    /// Requests the given animation file be played on the previewed model.
+    pub fn preview_animation(&self, file: PathBuf) {
+        let result = self
+            .channel
+            .unbounded_send(Message::PreviewAnimationRequest(file));
+
+        debug_assert!(result.is_ok());
+    }
+
     /// Notifies the app of a load result.
     pub fn load_update(&self, result: Result<(), String>) {
         let result = self.channel.unbounded_send(Message::LoadUpdate(result));
@@ -62,6 +71,22 @@ impl Controller {
         debug_assert!(result.is_ok());
     }
 
+    /// Notifies the app that watched files changed on disk, with the removed files, and whether the previewed asset changed.
+    ///
+    /// `moved` holds, for every row visible before the change, the row its asset is visible at now, if it still is.
+    pub fn assets_changed(
+        &self,
+        removed: Vec<PathBuf>,
+        moved: Vec<Option<usize>>,
+        preview_changed: bool,
+    ) {
+        let result =
+            self.channel
+                .unbounded_send(Message::AssetsChanged(removed, moved, preview_changed));
+
+        debug_assert!(result.is_ok());
+    }
+
     /// Notifies the app of a preview asset being ready.
     pub fn preview_update(&self, request_id: u64, asset: AssetPreview) {
         let result = self
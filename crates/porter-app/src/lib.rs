@@ -8,12 +8,16 @@ pub(crate) mod widgets;
 #[cfg(target_os = "windows")]
 pub(crate) mod icon_windows;
 
+mod animation_player;
 mod app;
 mod app_state;
 mod asset_column;
+mod asset_issue;
 mod asset_manager;
 mod asset_preview;
 mod asset_status;
+mod asset_tree;
+mod blend_shapes;
 mod column_status;
 mod controller;
 mod executor;
@@ -24,8 +28,10 @@ mod settings;
 mod sort;
 mod windows;
 
+pub(crate) use animation_player::*;
 pub(crate) use app::*;
 pub(crate) use asset_column::*;
+pub(crate) use blend_shapes::*;
 pub(crate) use executor::*;
 pub(crate) use message::*;
 pub(crate) use windows::*;
@@ -34,9 +40,11 @@ pub(crate) use windows::*;
 pub mod palette;
 
 pub use app_state::*;
+pub use asset_issue::*;
 pub use asset_manager::*;
 pub use asset_preview::*;
 pub use asset_status::*;
+pub use asset_tree::*;
 pub use column_status::*;
 pub use controller::*;
 pub use icon::*;
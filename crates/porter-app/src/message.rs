@@ -29,11 +29,13 @@ pub enum Message {
     PreviewWindow(PreviewWindowMessage),
     LoadUpdate(Result<(), String>),
     ProgressUpdate(bool, u32),
+    AssetsChanged(Vec<PathBuf>, Vec<Option<usize>>, bool),
     PreviewUpdate(u64, AssetPreview),
     PreviewWindowCreate,
     PreviewWindowClosed,
     PreviewToggle,
     PreviewRequest,
+    PreviewAnimationRequest(PathBuf),
     ExportSelected,
     ExportAll,
     ExportCancel,
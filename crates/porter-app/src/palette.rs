@@ -80,6 +80,8 @@ pub const ASSET_STATUS_PLACEHOLDER: Color = Color::from_rgb8(0xEC, 0x34, 0xCA);
 pub const ASSET_STATUS_EXPORTING: Color = Color::from_rgb8(0x90, 0x7A, 0xD6);
 /// Asset status not supported color.
 pub const ASSET_STATUS_NOT_SUPPORTED: Color = Color::from_rgb8(0xF1, 0xA3, 0x8B);
+/// Asset status changed color.
+pub const ASSET_STATUS_CHANGED: Color = Color::from_rgb8(0xF2, 0x8C, 0x28);
 
 /// Asset type image color.
 pub const ASSET_TYPE_IMAGE: Color = Color::from_rgb8(0xCA, 0x61, 0xC3);
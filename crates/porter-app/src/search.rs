@@ -17,11 +17,21 @@ impl Default for SearchRange {
 }
 
 /// Ways to filter on a search name.
+#[derive(Clone)]
 enum SearchName {
     Contained(String),
     NotContained(String),
 }
 
+/// Ways to filter on a tag, by its key and value.
+#[derive(Clone)]
+struct SearchTag {
+    key: String,
+    value: SearchName,
+    /// The whole `key:value` command, searched for in names when no asset has the key.
+    name: SearchName,
+}
+
 /// The searchable data for an asset.
 pub struct SearchAsset {
     bone_count: usize,
@@ -31,6 +41,7 @@ pub struct SearchAsset {
     width: usize,
     height: usize,
     name: String,
+    tags: Vec<(String, String)>,
 }
 
 impl SearchAsset {
@@ -44,6 +55,7 @@ impl SearchAsset {
             width: 0,
             height: 0,
             name,
+            tags: Vec::new(),
         }
     }
 
@@ -82,10 +94,17 @@ impl SearchAsset {
         self.height = height;
         self
     }
+
+    /// Adds a tag this asset has, searched for with `key:value`.
+    pub fn tag(mut self, key: &str, value: &str) -> Self {
+        self.tags.push((key.to_lowercase(), value.to_lowercase()));
+        self
+    }
 }
 
 /// A compiled search term.
 #[repr(align(64))]
+#[derive(Clone)]
 pub struct SearchTerm {
     bone_count: SearchRange,
     mesh_count: SearchRange,
@@ -94,6 +113,7 @@ pub struct SearchTerm {
     width: SearchRange,
     height: SearchRange,
     search_names: [Option<SearchName>; 5],
+    search_tags: [Option<SearchTag>; 5],
 }
 
 impl SearchTerm {
@@ -115,6 +135,9 @@ impl SearchTerm {
         let mut search_names: [Option<SearchName>; 5] = [const { None }; 5];
         let mut search_names_index = 0;
 
+        let mut search_tags: [Option<SearchTag>; 5] = [const { None }; 5];
+        let mut search_tags_index = 0;
+
         for command in commands {
             if let Some(command) = command.strip_prefix("bonecount:") {
                 let _ = parse_search_number(command, &mut bone_count);
@@ -128,6 +151,9 @@ impl SearchTerm {
                 let _ = parse_search_number(command, &mut width);
             } else if let Some(command) = command.strip_prefix("height:") {
                 let _ = parse_search_number(command, &mut height);
+            } else if let Some(tag) = parse_search_tag(command) {
+                search_tags[search_tags_index] = Some(tag);
+                search_tags_index += 1;
             } else if let Some(command) = command.strip_prefix('!') {
                 let command = command.trim();
 
@@ -155,9 +181,35 @@ impl SearchTerm {
             width,
             height,
             search_names,
+            search_tags,
         }
     }
 
+    /// Searches for the tags whose key isn't known by name instead, so names containing a colon can still be found.
+    ///
+    /// Tags are only known to the asset manager, which passes whether any of its assets has the given key.
+    pub fn with_known_tags(&self, is_known: impl Fn(&str) -> bool) -> Self {
+        let mut search = self.clone();
+
+        let mut search_names_index = search
+            .search_names
+            .iter()
+            .take_while(|name| name.is_some())
+            .count();
+
+        for tag in &mut search.search_tags {
+            if tag.as_ref().is_some_and(|tag| !is_known(&tag.key)) {
+                search.search_names[search_names_index] = tag.take().map(|tag| tag.name);
+                search_names_index += 1;
+            }
+        }
+
+        // Keep the remaining tags packed at the front, matching stops at the first empty slot.
+        search.search_tags.sort_by_key(|tag| tag.is_none());
+
+        search
+    }
+
     /// Determines if the given asset matches this search command.
     #[inline(always)]
     pub fn matches(&self, asset: SearchAsset) -> bool {
@@ -199,6 +251,29 @@ impl SearchTerm {
             }
         }
 
+        let mut tags = self.search_tags.iter();
+
+        while let Some(Some(tag)) = tags.next() {
+            let mut values = asset
+                .tags
+                .iter()
+                .filter(|(key, _)| *key == tag.key)
+                .map(|(_, value)| value);
+
+            match &tag.value {
+                SearchName::Contained(value) => {
+                    if !values.any(|tag_value| tag_value.contains(value.as_str())) {
+                        return false;
+                    }
+                }
+                SearchName::NotContained(value) => {
+                    if values.any(|tag_value| tag_value.contains(value.as_str())) {
+                        return false;
+                    }
+                }
+            }
+        }
+
         true
     }
 }
@@ -231,3 +306,92 @@ fn parse_search_number(number: &str, range: &mut SearchRange) -> Result<(), Pars
 
     Ok(())
 }
+
+/// Parses a `key:value` or `!key:value` search command into a search tag.
+#[inline(always)]
+fn parse_search_tag(command: &str) -> Option<SearchTag> {
+    let command = command.trim();
+
+    let (command, not_contained) = match command.strip_prefix('!') {
+        Some(command) => (command, true),
+        None => (command, false),
+    };
+
+    let (key, value) = command.split_once(':')?;
+
+    if key.is_empty()
+        || !key
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+    {
+        return None;
+    }
+
+    let value = value.trim().to_owned();
+
+    let search_name = |value: String| {
+        if not_contained {
+            SearchName::NotContained(value)
+        } else {
+            SearchName::Contained(value)
+        }
+    };
+
+    Some(SearchTag {
+        key: key.to_owned(),
+        name: search_name(command.to_owned()),
+        value: search_name(value),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(name: &str, tags: &[(&str, &str)]) -> SearchAsset {
+        tags.iter()
+            .fold(SearchAsset::new(name.to_owned()), |asset, (key, value)| {
+                asset.tag(key, value)
+            })
+    }
+
+    #[test]
+    fn parses_search_tags() {
+        let tag = parse_search_tag(" !Author:Bob ").unwrap();
+
+        assert_eq!(tag.key, "Author");
+        assert!(matches!(&tag.value, SearchName::NotContained(value) if value == "Bob"));
+        assert!(matches!(&tag.name, SearchName::NotContained(name) if name == "Author:Bob"));
+
+        assert!(parse_search_tag("up axis:y").is_none());
+        assert!(parse_search_tag(":y").is_none());
+        assert!(parse_search_tag("body").is_none());
+    }
+
+    #[test]
+    fn matches_known_tags() {
+        let search =
+            SearchTerm::compile("author:bob".to_owned()).with_known_tags(|key| key == "author");
+
+        assert!(search.matches(asset("body", &[("Author", "Bobby")])));
+        assert!(!search.matches(asset("body", &[("Author", "Alice")])));
+        assert!(!search.matches(asset("author:bob", &[])));
+
+        let search = SearchTerm::compile("!author:bob, body".to_owned())
+            .with_known_tags(|key| key == "author");
+
+        assert!(search.matches(asset("body", &[("author", "alice")])));
+        assert!(!search.matches(asset("body", &[("author", "bob")])));
+        assert!(!search.matches(asset("head", &[("author", "alice")])));
+    }
+
+    #[test]
+    fn matches_unknown_tags_by_name() {
+        let search = SearchTerm::compile("weapon:ak, !author:bob".to_owned())
+            .with_known_tags(|key| key == "author");
+
+        assert!(search.matches(asset("weapon:ak47", &[("author", "alice")])));
+        assert!(!search.matches(asset("weapon:ak47", &[("author", "bob")])));
+        assert!(!search.matches(asset("weapon_ak47", &[])));
+    }
+}
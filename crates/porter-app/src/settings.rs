@@ -36,6 +36,7 @@ bitflags! {
         const LOAD_SOUNDS = 1 << 4;
         const LOAD_RAW_FILES = 1 << 5;
         const LOAD_FORCE_RAW_FILES = 1 << 6;
+        const WATCH_FILES = 1 << 7;
     }
 }
 
@@ -49,6 +50,7 @@ bitflags! {
         const EXPORT_CAST = 1 << 5;
         const EXPORT_MAYA = 1 << 6;
         const EXPORT_FBX = 1 << 7;
+        const EXPORT_HAIR_AS_MESHES = 1 << 8;
     }
 }
 
@@ -81,6 +83,9 @@ pub enum PreviewControlScheme {
     Blender,
 }
 
+/// Version of the saved settings layout, bumped whenever a field is added.
+const SETTINGS_VERSION: u32 = 2;
+
 /// Global application settings.
 #[derive(Debug, Decode, Encode, Clone)]
 pub struct Settings {
@@ -98,6 +103,48 @@ pub struct Settings {
     far_clip: u32,
     preview_window: bool,
     custom_scale: Option<f32>,
+    texture_directories: Vec<PathBuf>,
+}
+
+/// Settings as saved by version 1, before texture directories were added.
+#[derive(Debug, Decode, Encode)]
+struct SettingsV1 {
+    _version: u32,
+    load_settings: LoadSettings,
+    model_settings: ModelSettings,
+    anim_settings: AnimSettings,
+    audio_settings: AudioSettings,
+    image_file_type: ImageFileType,
+    image_normal_map_processing: ImageNormalMapProcessing,
+    output_directory: Option<PathBuf>,
+    preview_controls: PreviewControlScheme,
+    preview_overlay: bool,
+    auto_scale: bool,
+    far_clip: u32,
+    preview_window: bool,
+    custom_scale: Option<f32>,
+}
+
+impl From<SettingsV1> for Settings {
+    fn from(settings: SettingsV1) -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            load_settings: settings.load_settings,
+            model_settings: settings.model_settings,
+            anim_settings: settings.anim_settings,
+            audio_settings: settings.audio_settings,
+            image_file_type: settings.image_file_type,
+            image_normal_map_processing: settings.image_normal_map_processing,
+            output_directory: settings.output_directory,
+            preview_controls: settings.preview_controls,
+            preview_overlay: settings.preview_overlay,
+            auto_scale: settings.auto_scale,
+            far_clip: settings.far_clip,
+            preview_window: settings.preview_window,
+            custom_scale: settings.custom_scale,
+            texture_directories: Vec::new(),
+        }
+    }
 }
 
 impl Settings {
@@ -113,13 +160,24 @@ impl Settings {
                 .join(name.into().to_lowercase())
                 .with_extension("dat"),
         )
-        .map_or(Default::default(), |buffer| {
-            let config = bincode::config::standard();
+        .map_or(Default::default(), |buffer| Self::decode(&buffer))
+    }
 
-            bincode::decode_from_slice(&buffer, config)
-                .unwrap_or_default()
-                .0
-        })
+    /// Decodes saved settings, upgrading older layouts, or returns new ones.
+    fn decode(buffer: &[u8]) -> Settings {
+        let config = bincode::config::standard();
+
+        // The version comes first, and decides the layout of the rest.
+        match bincode::decode_from_slice::<u32, _>(buffer, config) {
+            Ok((1, _)) => bincode::decode_from_slice::<SettingsV1, _>(buffer, config)
+                .map(|(settings, _)| Settings::from(settings))
+                .unwrap_or_default(),
+            _ => {
+                bincode::decode_from_slice(buffer, config)
+                    .unwrap_or_default()
+                    .0
+            }
+        }
     }
 
     /// Saves the settings to the disk at the given path.
@@ -237,6 +295,16 @@ impl Settings {
             .set(LoadSettings::LOAD_FORCE_RAW_FILES, value)
     }
 
+    /// Whether or not to watch loaded files for changes.
+    pub fn watch_files(&self) -> bool {
+        self.load_settings.contains(LoadSettings::WATCH_FILES)
+    }
+
+    /// Sets whether or not to watch loaded files for changes.
+    pub fn set_watch_files(&mut self, value: bool) {
+        self.load_settings.set(LoadSettings::WATCH_FILES, value)
+    }
+
     /// The model file types to export to.
     pub fn model_file_types(&self) -> Vec<ModelFileType> {
         let mut result = Vec::with_capacity(8);
@@ -290,6 +358,18 @@ impl Settings {
         self.model_settings.set(flag, value);
     }
 
+    /// Whether or not to convert hair to meshes when exporting models.
+    pub fn export_hair_as_meshes(&self) -> bool {
+        self.model_settings
+            .contains(ModelSettings::EXPORT_HAIR_AS_MESHES)
+    }
+
+    /// Sets whether or not to convert hair to meshes when exporting models.
+    pub fn set_export_hair_as_meshes(&mut self, value: bool) {
+        self.model_settings
+            .set(ModelSettings::EXPORT_HAIR_AS_MESHES, value)
+    }
+
     /// The animation file types to export to.
     pub fn anim_file_types(&self) -> Vec<AnimationFileType> {
         let mut result = Vec::with_capacity(1);
@@ -394,6 +474,25 @@ impl Settings {
         self.output_directory = Some(path);
     }
 
+    /// Additional directories searched for textures that aren't next to the asset.
+    pub fn texture_directories(&self) -> &[PathBuf] {
+        &self.texture_directories
+    }
+
+    /// Adds a directory to search for textures, unless it's already searched.
+    pub fn add_texture_directory(&mut self, path: PathBuf) {
+        if !self.texture_directories.contains(&path) {
+            self.texture_directories.push(path);
+        }
+    }
+
+    /// Removes the texture directory at the given index.
+    pub fn remove_texture_directory(&mut self, index: usize) {
+        if index < self.texture_directories.len() {
+            self.texture_directories.remove(index);
+        }
+    }
+
     /// Gets the preview control scheme.
     pub fn preview_controls(&self) -> PreviewControlScheme {
         self.preview_controls
@@ -473,10 +572,11 @@ impl Settings {
 impl Default for Settings {
     fn default() -> Self {
         Self {
-            version: 1,
+            version: SETTINGS_VERSION,
             load_settings: LoadSettings::all()
                 & !LoadSettings::LOAD_RAW_FILES
-                & !LoadSettings::LOAD_FORCE_RAW_FILES,
+                & !LoadSettings::LOAD_FORCE_RAW_FILES
+                & !LoadSettings::WATCH_FILES,
             model_settings: ModelSettings::EXPORT_CAST,
             anim_settings: AnimSettings::EXPORT_CAST,
             audio_settings: AudioSettings::EXPORT_WAV,
@@ -489,6 +589,57 @@ impl Default for Settings {
             far_clip: 10000,
             preview_window: false,
             custom_scale: None,
+            texture_directories: Vec::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upgrades_version_1_settings() {
+        let saved = SettingsV1 {
+            _version: 1,
+            load_settings: LoadSettings::LOAD_MODELS,
+            model_settings: ModelSettings::EXPORT_OBJ,
+            anim_settings: AnimSettings::EXPORT_CAST,
+            audio_settings: AudioSettings::EXPORT_WAV,
+            image_file_type: ImageFileType::Tga,
+            image_normal_map_processing: ImageNormalMapProcessing::DirectX,
+            output_directory: Some(PathBuf::from("exports")),
+            preview_controls: PreviewControlScheme::Blender,
+            preview_overlay: false,
+            auto_scale: false,
+            far_clip: 500,
+            preview_window: true,
+            custom_scale: Some(2.0),
+        };
+
+        let buffer = bincode::encode_to_vec(&saved, bincode::config::standard()).unwrap();
+
+        let settings = Settings::decode(&buffer);
+
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert!(settings.load_models());
+        assert!(!settings.load_animations());
+        assert_eq!(settings.far_clip, 500);
+        assert_eq!(settings.custom_scale, Some(2.0));
+        assert_eq!(settings.output_directory, Some(PathBuf::from("exports")));
+        assert!(settings.texture_directories.is_empty());
+    }
+
+    #[test]
+    fn keeps_current_settings() {
+        let mut saved = Settings::default();
+
+        saved.add_texture_directory(PathBuf::from("textures"));
+
+        let buffer = bincode::encode_to_vec(&saved, bincode::config::standard()).unwrap();
+
+        let settings = Settings::decode(&buffer);
+
+        assert_eq!(settings.texture_directories, [PathBuf::from("textures")]);
+    }
+}
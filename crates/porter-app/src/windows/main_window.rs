@@ -64,6 +64,7 @@ pub enum MainMessage {
     LoadFile,
     LoadFolder,
     PickExportFolder,
+    PickTextureFolder,
     Warning(String),
 }
 
@@ -112,6 +113,7 @@ impl MainWindow {
             LoadFile => self.on_load_file(state),
             LoadFolder => self.on_load_directory(state),
             PickExportFolder => self.on_pick_export_folder(state),
+            PickTextureFolder => self.on_pick_texture_folder(state),
             Warning(message) => self.on_warning(state, message),
         }
     }
@@ -343,6 +345,28 @@ impl MainWindow {
         })
     }
 
+    /// Occurs when the user wants to add a folder to search for textures.
+    fn on_pick_texture_folder(&mut self, state: &mut AppState) -> Task<Message> {
+        let mut settings = state.settings.clone();
+
+        let title = format!("{} | Select a texture directory", state.name.to_titlecase());
+
+        window::run_with_handle(self.id, move |handle| {
+            let path = FileDialog::new()
+                .set_parent(&handle)
+                .set_title(title)
+                .pick_folder();
+
+            if let Some(path) = path {
+                settings.add_texture_directory(path);
+
+                Message::from(SettingsMessage::Save(settings))
+            } else {
+                Message::Noop
+            }
+        })
+    }
+
     /// Show a warning to the user.
     fn on_warning(&mut self, state: &mut AppState, message: String) -> Task<Message> {
         let title = state.name.to_titlecase();